@@ -0,0 +1,223 @@
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+use document::Preamble;
+use failure::{Error, ResultExt};
+use paragraph::ParagraphElement;
+
+/// How code blocks and inline code get rendered.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CodeBackend {
+    /// The built-in `verbatim` environment and `\verb`. These don't need any
+    /// packages, but ignore everything except the code itself.
+    Verbatim,
+    /// The `listings` package.
+    Listings,
+    /// The `minted` package, which needs `-shell-escape` and Pygments.
+    Minted,
+    /// Highlight the code in Rust (see `highlight()`) and emit a `fancyvrb`
+    /// `Verbatim` environment with `\textcolor` markup, which compiles with
+    /// plain `pdflatex`.
+    ///
+    /// # Note
+    ///
+    /// Like `Verbatim`, this ignores the caption, label and highlighted
+    /// lines. Code blocks can only be printed with the `highlight` feature
+    /// enabled, otherwise printing them fails with an error.
+    Highlighted,
+}
+
+impl Default for CodeBackend {
+    fn default() -> Self {
+        CodeBackend::Verbatim
+    }
+}
+
+impl CodeBackend {
+    /// The packages this backend needs.
+    pub fn packages(&self) -> &[&str] {
+        match *self {
+            CodeBackend::Verbatim => &[],
+            CodeBackend::Listings => &["listings"],
+            CodeBackend::Minted => &["minted"],
+            CodeBackend::Highlighted => &["fancyvrb", "xcolor"],
+        }
+    }
+
+    /// Add any packages this backend needs to a `Preamble`.
+    pub fn setup(&self, preamble: &mut Preamble) {
+        for package in self.packages() {
+            preamble.require_package(package);
+        }
+    }
+}
+
+/// A block of source code.
+///
+/// How the block is rendered depends on the `CodeBackend` selected on the
+/// `Document`.
+///
+/// # Examples
+///
+/// ```rust
+/// use latex::{CodeBackend, CodeBlock, Document, DocumentClass};
+///
+/// let mut doc = Document::new(DocumentClass::Article);
+/// doc.use_code_backend(CodeBackend::Listings);
+///
+/// let mut block = CodeBlock::new("fn main() {\n    println!(\"Hello\");\n}");
+/// block
+///     .language("C")
+///     .caption("The obligatory example")
+///     .label("lst:hello")
+///     .line_numbers();
+/// doc.push(block);
+/// ```
+///
+/// With the `listings` backend the block would be rendered as:
+///
+/// ```tex
+/// \begin{lstlisting}[language=C, caption={The obligatory example}, label=lst:hello, numbers=left]
+/// fn main() {
+///     println!("Hello");
+/// }
+/// \end{lstlisting}
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CodeBlock {
+    /// The code itself.
+    pub code: String,
+    /// The language to highlight the code as.
+    pub language: Option<String>,
+    /// An optional caption.
+    pub caption: Option<ParagraphElement>,
+    /// A label for referencing the listing.
+    pub label: Option<String>,
+    /// Should each line be numbered?
+    pub line_numbers: bool,
+    /// The number given to the first line, if it isn't `1`.
+    pub first_line: Option<usize>,
+    /// Lines to highlight, counted from `1` relative to the start of the
+    /// block (only supported by `minted`). The `listings` backend fails with
+    /// an error rather than dropping them.
+    pub highlight_lines: Vec<usize>,
+}
+
+impl CodeBlock {
+    /// Create a new block of code.
+    pub fn new<S: AsRef<str>>(code: S) -> CodeBlock {
+        CodeBlock {
+            code: code.as_ref().to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Load a block of code from a file, optionally only taking a range of
+    /// lines (counted from `1`, inclusive).
+    ///
+    /// When a range is given the block's first line number is set to the
+    /// start of the range, so the line numbers match the original file.
+    pub fn from_file<P: AsRef<Path>>(
+        path: P,
+        lines: Option<RangeInclusive<usize>>,
+    ) -> Result<CodeBlock, Error> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .with_context(|_| format!("Unable to read {}", path.display()))?;
+
+        let range = match lines {
+            Some(range) => range,
+            None => return Ok(CodeBlock::new(contents)),
+        };
+
+        if *range.start() == 0 || range.start() > range.end() {
+            bail!("Invalid line range {:?}, lines are counted from 1", range);
+        }
+
+        let total = contents.lines().count();
+        if *range.end() > total {
+            bail!(
+                "{} only has {} lines, but lines {:?} were requested",
+                path.display(),
+                total,
+                range
+            );
+        }
+
+        let selected: Vec<&str> = contents
+            .lines()
+            .skip(range.start() - 1)
+            .take(range.end() - range.start() + 1)
+            .collect();
+
+        let mut block = CodeBlock::new(selected.join("\n"));
+        block.first_line(*range.start());
+        Ok(block)
+    }
+
+    /// Set the language to highlight the code as.
+    pub fn language(&mut self, language: &str) -> &mut Self {
+        self.language = Some(language.to_string());
+        self
+    }
+
+    /// Give the block a caption.
+    pub fn caption<P: Into<ParagraphElement>>(&mut self, caption: P) -> &mut Self {
+        self.caption = Some(caption.into());
+        self
+    }
+
+    /// Give the block a label.
+    pub fn label(&mut self, name: &str) -> &mut Self {
+        self.label = Some(name.to_string());
+        self
+    }
+
+    /// Number each line.
+    pub fn line_numbers(&mut self) -> &mut Self {
+        self.line_numbers = true;
+        self
+    }
+
+    /// Set the number given to the first line.
+    pub fn first_line(&mut self, number: usize) -> &mut Self {
+        self.first_line = Some(number);
+        self
+    }
+
+    /// Highlight some lines.
+    pub fn highlight<I>(&mut self, lines: I) -> &mut Self
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        self.highlight_lines.extend(lines);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::process;
+
+    #[test]
+    fn load_a_range_of_lines() {
+        let path = env::temp_dir().join(format!("latex-rs-code-{}.py", process::id()));
+        fs::write(&path, "import os\n\ndef main():\n    print(os.getcwd())\n").unwrap();
+
+        let block = CodeBlock::from_file(&path, Some(3..=4)).unwrap();
+        let too_far = CodeBlock::from_file(&path, Some(3..=10));
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(block.code, "def main():\n    print(os.getcwd())");
+        assert_eq!(block.first_line, Some(3));
+        assert!(too_far.is_err());
+    }
+
+    #[test]
+    fn missing_files_are_an_error() {
+        assert!(CodeBlock::from_file("/this/file/does/not/exist.rs", None).is_err());
+    }
+}
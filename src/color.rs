@@ -0,0 +1,222 @@
+use failure::Error;
+
+/// The colours `xcolor` always knows about.
+const BASE_COLORS: &[&str] = &[
+    "black",
+    "blue",
+    "brown",
+    "cyan",
+    "darkgray",
+    "gray",
+    "green",
+    "lightgray",
+    "lime",
+    "magenta",
+    "olive",
+    "orange",
+    "pink",
+    "purple",
+    "red",
+    "teal",
+    "violet",
+    "white",
+    "yellow",
+];
+
+/// A colour, as understood by the `xcolor` package.
+///
+/// Named colours and mixes can be used anywhere, but a mix can only be made
+/// from named colours. Give an `Rgb` or `Html` colour a name with
+/// `Preamble::define_color()` if you want to mix it.
+///
+/// Colours can be used for text (`ParagraphElement::colored()`) and boxes
+/// (`ParagraphElement::color_box()` and `framed_color_box()`). There are no
+/// tables yet, so there's no `\rowcolor` or `\cellcolor` either.
+///
+/// # Examples
+///
+/// ```rust
+/// use latex::{Color, Paragraph, ParagraphElement, Preamble};
+///
+/// let mut preamble = Preamble::default();
+/// let brand = preamble.define_color("brand", Color::Html(0x1F6FEB));
+///
+/// let mut para = Paragraph::new();
+/// para.push(ParagraphElement::colored(brand.clone(), "ACME"))
+///     .push(" ")
+///     .push(ParagraphElement::color_box(brand.percent(20), "Note"))
+///     .push(" ")
+///     .push(ParagraphElement::colored(Color::Rgb(255, 0, 0), "Stop"));
+///
+/// assert_eq!(
+///     para.to_string(),
+///     "\\textcolor{brand}{ACME} \\colorbox{brand!20}{Note} \\textcolor[RGB]{255,0,0}{Stop}\n"
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum Color {
+    /// A colour with a name, either one of `xcolor`'s base colours (`red`,
+    /// `teal`, ...) or one defined in the `Preamble`.
+    Named(String),
+    /// A colour made from red, green and blue components.
+    Rgb(u8, u8, u8),
+    /// A colour written as a hex code, e.g. `Color::Html(0xFF8800)`. It
+    /// can be at most `0xFFFFFF`.
+    Html(u32),
+    /// A percentage of one colour mixed with another (or white, if there
+    /// isn't another), e.g. `red!20` or `red!20!blue`.
+    Mix(Box<Color>, u8, Option<Box<Color>>),
+}
+
+impl Color {
+    /// Create a named colour.
+    pub fn named(name: &str) -> Color {
+        Color::Named(name.to_string())
+    }
+
+    /// Mix a percentage of this colour with white (`red!20`).
+    pub fn percent(self, percent: u8) -> Color {
+        Color::Mix(Box::new(self), percent, None)
+    }
+
+    /// Mix a percentage of this colour with another (`red!20!blue`).
+    pub fn mix(self, percent: u8, other: Color) -> Color {
+        Color::Mix(Box::new(self), percent, Some(Box::new(other)))
+    }
+
+    /// Is this one of `xcolor`'s base colours?
+    pub fn is_base_color(name: &str) -> bool {
+        BASE_COLORS.contains(&name)
+    }
+
+    /// The colour model for colours which need one (`RGB` or `HTML`).
+    pub fn model(&self) -> Option<&'static str> {
+        match *self {
+            Color::Rgb(..) => Some("RGB"),
+            Color::Html(_) => Some("HTML"),
+            Color::Named(_) | Color::Mix(..) => None,
+        }
+    }
+
+    /// The colour's value, without any model. This is an `xcolor`
+    /// expression (e.g. `red!20`) for `Named` and `Mix` colours.
+    pub fn spec(&self) -> Result<String, Error> {
+        match *self {
+            Color::Named(ref name) => Ok(name.clone()),
+            Color::Rgb(r, g, b) => Ok(format!("{},{},{}", r, g, b)),
+            Color::Html(hex) if hex > 0xFF_FFFF => {
+                bail!("{:#X} is too big to be an HTML colour", hex)
+            }
+            Color::Html(hex) => Ok(format!("{:06X}", hex)),
+            Color::Mix(ref base, percent, ref other) => {
+                if percent > 100 {
+                    bail!("Can't mix in {}% of a colour", percent);
+                }
+
+                let mut spec = expression(base)?;
+                // the white in "red!20" is implicit, but only at the end
+                if let Color::Mix(_, _, None) = **base {
+                    spec.push_str("!white");
+                }
+                spec.push_str(&format!("!{}", percent));
+                if let Some(ref other) = *other {
+                    // "red!20!blue!50" means (red!20!blue)!50, not red!20!(blue!50)
+                    if let Color::Mix(..) = **other {
+                        bail!("Only the first colour in a mix can be a mix itself");
+                    }
+                    spec.push('!');
+                    spec.push_str(&expression(other)?);
+                }
+                Ok(spec)
+            }
+        }
+    }
+
+    /// Every named colour this colour refers to.
+    pub fn names(&self) -> Vec<&str> {
+        match *self {
+            Color::Named(ref name) => vec![name.as_str()],
+            Color::Rgb(..) | Color::Html(_) => Vec::new(),
+            Color::Mix(ref base, _, ref other) => {
+                let mut names = base.names();
+                if let Some(ref other) = *other {
+                    names.extend(other.names());
+                }
+                names
+            }
+        }
+    }
+
+    /// Write the colour as it is passed to a macro like `\textcolor`, e.g.
+    /// `{red!20}` or `[RGB]{255,0,0}`.
+    pub fn to_argument(&self) -> Result<String, Error> {
+        match self.model() {
+            Some(model) => Ok(format!("[{}]{{{}}}", model, self.spec()?)),
+            None => Ok(format!("{{{}}}", self.spec()?)),
+        }
+    }
+}
+
+/// The spec for a colour which is used inside a mix.
+fn expression(color: &Color) -> Result<String, Error> {
+    if color.model().is_some() {
+        bail!(
+            "{:?} can't be mixed, give it a name with Preamble::define_color() first",
+            color
+        );
+    }
+    color.spec()
+}
+
+impl<'a> From<&'a str> for Color {
+    fn from(other: &'a str) -> Color {
+        Color::named(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_arguments() {
+        let inputs = vec![
+            (Color::named("red"), "{red}"),
+            (Color::Rgb(0, 128, 255), "[RGB]{0,128,255}"),
+            (Color::Html(0xff8800), "[HTML]{FF8800}"),
+            (Color::Html(0x00_00ff), "[HTML]{0000FF}"),
+            (Color::named("red").percent(20), "{red!20}"),
+            (Color::named("red").mix(30, "blue".into()), "{red!30!blue}"),
+            (
+                Color::named("red").percent(50).mix(20, "black".into()),
+                "{red!50!white!20!black}",
+            ),
+        ];
+
+        for (color, should_be) in inputs {
+            assert_eq!(color.to_argument().unwrap(), should_be);
+        }
+    }
+
+    #[test]
+    fn invalid_colors() {
+        let inputs = vec![
+            Color::Rgb(1, 2, 3).percent(20),
+            Color::named("red").mix(20, Color::Html(0x123456)),
+            Color::named("red").mix(20, Color::named("blue").percent(10)),
+            Color::named("red").percent(120),
+            Color::Html(0x1_000000),
+        ];
+
+        for color in inputs {
+            assert!(color.spec().is_err(), "{:?}", color);
+        }
+    }
+
+    #[test]
+    fn names_in_a_mix() {
+        let color = Color::named("brand").percent(40).mix(10, "black".into());
+
+        assert_eq!(color.names(), vec!["brand", "black"]);
+    }
+}
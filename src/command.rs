@@ -0,0 +1,335 @@
+use document::Element;
+use environment::Environment;
+use failure::Error;
+use paragraph::ParagraphElement;
+
+/// A single argument passed to a `Command`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Argument {
+    /// An optional argument, written as `[...]`.
+    Optional(ParagraphElement),
+    /// A mandatory argument, written as `{...}`.
+    Mandatory(ParagraphElement),
+}
+
+impl Argument {
+    /// Get the argument's contents.
+    pub fn value(&self) -> &ParagraphElement {
+        match *self {
+            Argument::Optional(ref value) | Argument::Mandatory(ref value) => value,
+        }
+    }
+}
+
+/// A call to an arbitrary macro.
+///
+/// This can be used both as an `Element` (e.g. `\vspace{1em}` on a line of
+/// its own) and inline as a `ParagraphElement`. Arguments are written in the
+/// order they were added.
+///
+/// # Examples
+///
+/// ```rust
+/// use latex::Command;
+///
+/// let mut include = Command::new("includepdf");
+/// include.optional("pages=-").argument("x.pdf");
+///
+/// assert_eq!(include.to_string(), "\\includepdf[pages=-]{x.pdf}\n");
+/// ```
+///
+/// An optional argument containing a `]` gets wrapped in braces so it
+/// doesn't end the argument early.
+///
+/// ```rust
+/// use latex::{Command, ParagraphElement};
+///
+/// let mut item = Command::new("item");
+/// item.optional(ParagraphElement::InlineMath(String::from("[a, b]")));
+///
+/// assert_eq!(item.to_string(), "\\item[{$[a, b]$}]\n");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Command {
+    /// The macro's name, without the leading backslash.
+    pub name: String,
+    /// Use the starred form of the macro (e.g. `\vspace*`).
+    pub starred: bool,
+    /// The arguments passed to the macro.
+    pub arguments: Vec<Argument>,
+}
+
+impl Command {
+    /// Create a call to a macro with no arguments.
+    pub fn new(name: &str) -> Command {
+        Command {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Use the starred form of the macro.
+    pub fn starred(&mut self) -> &mut Self {
+        self.starred = true;
+        self
+    }
+
+    /// Add an optional argument.
+    pub fn optional<P: Into<ParagraphElement>>(&mut self, arg: P) -> &mut Self {
+        self.arguments.push(Argument::Optional(arg.into()));
+        self
+    }
+
+    /// Add a mandatory argument.
+    pub fn argument<P: Into<ParagraphElement>>(&mut self, arg: P) -> &mut Self {
+        self.arguments.push(Argument::Mandatory(arg.into()));
+        self
+    }
+
+    /// Is the name something LaTeX will parse as a single control sequence?
+    /// That's either a run of letters or exactly one other character.
+    pub fn has_valid_name(&self) -> bool {
+        let mut chars = self.name.chars();
+        match (chars.next(), chars.next()) {
+            (None, _) => false,
+            (Some(c), None) => !c.is_whitespace(),
+            _ => self.name.chars().all(|c| c.is_ascii_alphabetic()),
+        }
+    }
+}
+
+impl From<Command> for Element {
+    fn from(other: Command) -> Self {
+        Element::Command(other)
+    }
+}
+
+impl From<Command> for ParagraphElement {
+    fn from(other: Command) -> Self {
+        ParagraphElement::Command(other)
+    }
+}
+
+/// Work out which arguments are optional when calling something defined
+/// with `args_num` arguments, where the first one may have a default.
+fn split_arguments<I>(
+    name: &str,
+    args_num: usize,
+    has_default: bool,
+    args: I,
+) -> Result<Vec<Argument>, Error>
+where
+    I: IntoIterator,
+    I::Item: Into<ParagraphElement>,
+{
+    let args: Vec<ParagraphElement> = args.into_iter().map(Into::into).collect();
+    let mandatory = if has_default { args_num - 1 } else { args_num };
+
+    if args.len() == mandatory {
+        Ok(args.into_iter().map(Argument::Mandatory).collect())
+    } else if has_default && args.len() == args_num {
+        Ok(args
+            .into_iter()
+            .enumerate()
+            .map(|(i, arg)| match i {
+                0 => Argument::Optional(arg),
+                _ => Argument::Mandatory(arg),
+            })
+            .collect())
+    } else if has_default {
+        bail!(
+            "\\{} takes {} or {} arguments, but {} were given",
+            name,
+            mandatory,
+            args_num,
+            args.len()
+        )
+    } else {
+        bail!(
+            "\\{} takes {} arguments, but {} were given",
+            name,
+            args_num,
+            args.len()
+        )
+    }
+}
+
+/// A handle to a macro defined in the `Preamble` (e.g. with
+/// `Preamble::new_command()`), used to call it with the right number of
+/// arguments.
+///
+/// # Examples
+///
+/// ```rust
+/// use latex::{Paragraph, Preamble};
+///
+/// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut preamble = Preamble::default();
+/// let love = preamble.new_command("Love", 2, "#1 loves #2");
+///
+/// let mut para = Paragraph::new();
+/// para.push(love.call(vec!["Alice", "Bob"]).map_err(|e| e.compat())?);
+///
+/// assert_eq!(para.to_string(), "\\Love{Alice}{Bob}\n");
+/// assert!(love.call(vec!["Alice"]).is_err());
+/// # Ok(())
+/// # }
+/// # fn main() {
+/// # run().unwrap();
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Macro {
+    name: String,
+    args_num: usize,
+    has_default: bool,
+}
+
+impl Macro {
+    /// Create a handle to a macro taking `args_num` arguments, where the
+    /// first argument is optional if `has_default` is set.
+    pub fn new(name: &str, args_num: usize, has_default: bool) -> Macro {
+        Macro {
+            name: name.to_string(),
+            args_num,
+            has_default: has_default && args_num > 0,
+        }
+    }
+
+    /// The macro's name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The number of arguments the macro takes, including the optional one.
+    pub fn args_num(&self) -> usize {
+        self.args_num
+    }
+
+    /// Does the first argument have a default value?
+    pub fn has_default(&self) -> bool {
+        self.has_default
+    }
+
+    /// Call the macro.
+    ///
+    /// If the first argument has a default you can either leave it out or
+    /// pass every argument, in which case the first is written as `[...]`.
+    pub fn call<I>(&self, args: I) -> Result<Command, Error>
+    where
+        I: IntoIterator,
+        I::Item: Into<ParagraphElement>,
+    {
+        let arguments = split_arguments(&self.name, self.args_num, self.has_default, args)?;
+
+        Ok(Command {
+            name: self.name.clone(),
+            starred: false,
+            arguments,
+        })
+    }
+
+    /// Call a macro which doesn't need any arguments.
+    pub fn invoke(&self) -> Result<Command, Error> {
+        self.call(Vec::<ParagraphElement>::new())
+    }
+}
+
+/// A handle to an environment defined with `Preamble::new_environment()`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MacroEnvironment {
+    name: String,
+    args_num: usize,
+    has_default: bool,
+}
+
+impl MacroEnvironment {
+    /// Create a handle to an environment taking `args_num` arguments, where
+    /// the first argument is optional if `has_default` is set.
+    pub fn new(name: &str, args_num: usize, has_default: bool) -> MacroEnvironment {
+        MacroEnvironment {
+            name: name.to_string(),
+            args_num,
+            has_default: has_default && args_num > 0,
+        }
+    }
+
+    /// The environment's name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Create an empty `Environment` using this definition, checking the
+    /// number of arguments the same way as `Macro::call()`.
+    pub fn begin<I>(&self, args: I) -> Result<Environment, Error>
+    where
+        I: IntoIterator,
+        I::Item: Into<ParagraphElement>,
+    {
+        let mut env = Environment::new(&self.name);
+
+        for arg in split_arguments(&self.name, self.args_num, self.has_default, args)? {
+            match arg {
+                Argument::Optional(value) => env.optional(value),
+                Argument::Mandatory(value) => env.argument(value),
+            };
+        }
+
+        Ok(env)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_command_names() {
+        let inputs = vec![
+            ("vspace", true),
+            ("LaTeX", true),
+            (",", true),
+            ("\\", true),
+            ("", false),
+            ("my command", false),
+            ("section2", false),
+            (" ", false),
+        ];
+
+        for (name, should_be) in inputs {
+            assert_eq!(Command::new(name).has_valid_name(), should_be, "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn call_a_macro_with_a_default_argument() {
+        let greet = Macro::new("greet", 2, true);
+
+        let short = greet.call(vec!["World"]).unwrap();
+        let long = greet.call(vec!["Hi", "World"]).unwrap();
+
+        assert_eq!(
+            short.arguments,
+            vec![Argument::Mandatory(ParagraphElement::from("World"))]
+        );
+        assert_eq!(
+            long.arguments,
+            vec![
+                Argument::Optional(ParagraphElement::from("Hi")),
+                Argument::Mandatory(ParagraphElement::from("World")),
+            ]
+        );
+        assert!(greet.invoke().is_err());
+        assert!(greet.call(vec!["a", "b", "c"]).is_err());
+    }
+
+    #[test]
+    fn begin_a_custom_environment() {
+        let boxed = MacroEnvironment::new("boxed", 1, false);
+
+        let env = boxed.begin(vec!["Title"]).unwrap();
+
+        assert_eq!(env.arguments, vec![ParagraphElement::from("Title")]);
+        assert!(boxed.begin(Vec::<&str>::new()).is_err());
+    }
+}
@@ -1,11 +1,24 @@
+use failure::Error;
 use std::fmt::{self, Display, Formatter};
 use std::ops::Deref;
 use std::slice::Iter;
 
-use equations::Align;
+use code::{CodeBackend, CodeBlock};
+use color::Color;
+use command::{Command, Macro, MacroEnvironment};
+use environment::Environment;
+use equations::{Align, DisplayMath};
+use geometry::PageGeometry;
+use length::Length;
 use lists::List;
+use options::{resolve_options, ClassOption};
+use page_style::PageStyle;
 use paragraph::Paragraph;
-use section::{Chapter, Part, Section, Subsection};
+use section::{
+    shift_headings, Chapter, LevelOverflow, ParagraphSection, Part, Section, Subparagraph,
+    Subsection, Subsubsection,
+};
+use theorem::{Proof, Theorem, TheoremDefinition};
 
 /// The root Document node.
 #[derive(Clone, Debug, Default, PartialEq)]
@@ -14,8 +27,21 @@ pub struct Document {
     pub class: DocumentClass,
     /// The `Document`'s preamble.
     pub preamble: Preamble,
-    /// Document arguments, like `[12pt,oneside,a4paper]`
+    /// Options passed to the document class.
+    pub options: Vec<ClassOption>,
+    /// Raw document arguments, like `[12pt,oneside,a4paper]`. These are
+    /// written after `options` and checked in the same way.
     pub arguments: Vec<String>,
+    /// The deepest sectioning level shown in the table of contents
+    /// (`tocdepth`), e.g. `1` to only show sections.
+    pub toc_depth: Option<i8>,
+    /// The deepest sectioning level which is numbered (`secnumdepth`).
+    pub secnum_depth: Option<i8>,
+    /// Is the document being reviewed? Todo notes are only rendered in
+    /// review mode and are stripped otherwise.
+    pub review: bool,
+    /// How `CodeBlock`s and inline code are rendered.
+    pub code_backend: CodeBackend,
     /// The various elements inside this `Document`.
     elements: Vec<Element>,
 }
@@ -42,8 +68,44 @@ impl Document {
         self
     }
 
+    /// Add an option to the document class, unless it's already there.
+    pub fn class_option(&mut self, option: ClassOption) -> &mut Self {
+        if !self.options.contains(&option) {
+            self.options.push(option);
+        }
+        self
+    }
+
+    /// Get every option passed to the document class (both `options` and
+    /// the raw `arguments`), without duplicates.
+    ///
+    /// This fails if two options conflict (e.g. `a4paper` and
+    /// `letterpaper`) or the `DocumentClass` doesn't support one of them.
+    pub fn class_options(&self) -> Result<Vec<ClassOption>, Error> {
+        let mut options = self.options.clone();
+        for arg in &self.arguments {
+            options.push(arg.parse()?);
+        }
+
+        resolve_options(&self.class, options)
+    }
+
+    /// Select how code is rendered, adding any packages the backend needs to
+    /// the preamble.
+    pub fn use_code_backend(&mut self, backend: CodeBackend) -> &mut Self {
+        self.code_backend = backend;
+        backend.setup(&mut self.preamble);
+        self
+    }
+
+    /// Set the page layout, see `Preamble::page_geometry()`.
+    pub fn page_geometry(&mut self, geometry: PageGeometry) -> &mut Self {
+        self.preamble.page_geometry(geometry);
+        self
+    }
+
     /// Iterate over the Elements in this document.
-    pub fn iter(&self) -> Iter<Element> {
+    pub fn iter(&self) -> Iter<'_, Element> {
         self.elements.iter()
     }
 
@@ -55,6 +117,28 @@ impl Document {
         }
         self
     }
+
+    /// Include one document into another, promoting or demoting every
+    /// sectioning element by `by` levels along the way (see
+    /// `shift_headings()`).
+    ///
+    /// This is handy for embedding a standalone report as a chapter of a
+    /// book, where each `Section` needs to become a `Subsection` and so on.
+    /// If any heading can't be shifted nothing is added.
+    pub fn push_doc_shifted(
+        &mut self,
+        doc: &Document,
+        by: i8,
+        overflow: LevelOverflow,
+    ) -> Result<&mut Self, Error> {
+        let shifted = doc
+            .iter()
+            .map(|element| shift_headings(element.clone(), by, overflow))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.extend(shifted);
+        Ok(self)
+    }
 }
 
 impl Deref for Document {
@@ -75,10 +159,6 @@ impl Deref for Document {
 #[derive(Clone, Debug, PartialEq)]
 pub enum Element {
     /// A Part - one of sectioning elements, has -1 level.
-    ///
-    /// # Note
-    ///
-    /// Part is available only in `report` and `book` documents.
     Part(Part),
 
     /// A Chapter - one of sectioning elements, has 0 level.
@@ -87,12 +167,17 @@ pub enum Element {
     ///
     /// Chapter is available only in `report` and `book` documents.
     Chapter(Chapter),
-    /// A section - one of sectioning elements, has 2 level.
+    /// A section - one of sectioning elements, has 1 level.
     Section(Section),
-    /// A subsection - one of sectioning elements, has 3 level.
+    /// A subsection - one of sectioning elements, has 2 level.
     Subsection(Subsection),
-    /// A subsubsection - one of sectioning elements, has 4 level.
-    Subsubsection(Subsection),
+    /// A subsubsection - one of sectioning elements, has 3 level.
+    Subsubsection(Subsubsection),
+    /// A paragraph heading (`\paragraph`) - one of sectioning elements, has 4
+    /// level.
+    ParagraphSection(ParagraphSection),
+    /// A subparagraph - one of sectioning elements, has 5 level.
+    Subparagraph(Subparagraph),
 
     /// A bare paragraph.
     ///
@@ -109,11 +194,43 @@ pub enum Element {
     TitlePage,
     /// Clear the page.
     ClearPage,
+    /// Start a new page (`\newpage`).
+    NewPage,
+    /// Break the page here, stretching the current page to fill it
+    /// (`\pagebreak`).
+    PageBreak,
+    /// Vertical space (`\vspace`).
+    VSpace(Length),
+    /// Stretchable vertical space which fills the rest of the page
+    /// (`\vfill`).
+    VFill,
+    /// A small vertical skip (`\smallskip`).
+    SmallSkip,
+    /// A medium vertical skip (`\medskip`).
+    MedSkip,
+    /// A big vertical skip (`\bigskip`).
+    BigSkip,
+    /// Change the page layout from here on (`\newgeometry`). The paper
+    /// size and orientation can't be changed mid-document, so they're
+    /// ignored.
+    NewGeometry(PageGeometry),
+    /// Go back to the page layout from the preamble (`\restoregeometry`).
+    RestoreGeometry,
+    /// Use a page style from here on (`\pagestyle`).
+    PageStyle(String),
+    /// Use a page style for the current page only (`\thispagestyle`).
+    ThisPageStyle(String),
     /// An `align` environment for containing a bunch of equations.
     Align(Align),
+    /// Any other display-math environment (`equation`, `gather`, ...).
+    DisplayMath(DisplayMath),
 
-    /// A generic environment and its lines.
+    /// A generic environment and its lines, which are written out as-is. Use
+    /// `Element::Block` if the body should be made up of other elements.
     Environment(String, Vec<String>),
+    /// A generic environment with arguments and a body made up of other
+    /// elements.
+    Block(Environment),
 
     /// Any other element.
     ///
@@ -124,6 +241,14 @@ pub enum Element {
     UserDefined(String),
     /// A list.
     List(List),
+    /// A block of source code.
+    CodeBlock(CodeBlock),
+    /// A call to an arbitrary macro, on a line of its own.
+    Command(Command),
+    /// A theorem-like block (theorem, lemma, definition, ...).
+    Theorem(Theorem),
+    /// A proof.
+    Proof(Proof),
     /// A generic include statement
     Input(String),
 
@@ -151,15 +276,21 @@ impl From<List> for Element {
     }
 }
 
+impl From<CodeBlock> for Element {
+    fn from(other: CodeBlock) -> Self {
+        Element::CodeBlock(other)
+    }
+}
+
 impl From<Align> for Element {
     fn from(other: Align) -> Self {
         Element::Align(other)
     }
 }
 
-impl From<Section> for Element {
-    fn from(other: Section) -> Self {
-        Element::Section(other)
+impl From<DisplayMath> for Element {
+    fn from(other: DisplayMath) -> Self {
+        Element::DisplayMath(other)
     }
 }
 
@@ -235,6 +366,43 @@ pub enum PreambleElement {
         default_arg: Option<String>,
         definition: String,
     },
+    /// Redefine an existing command with `\renewcommand`.
+    RenewCommand {
+        name: String,
+        args_num: Option<usize>,
+        default_arg: Option<String>,
+        definition: String,
+    },
+    /// Define a command with `\providecommand`, unless it already exists.
+    ProvideCommand {
+        name: String,
+        args_num: Option<usize>,
+        default_arg: Option<String>,
+        definition: String,
+    },
+    /// Define an environment with `\newenvironment`.
+    NewEnvironment {
+        name: String,
+        args_num: Option<usize>,
+        default_arg: Option<String>,
+        begin: String,
+        end: String,
+    },
+    /// Declare a math operator like `\sin` with `\DeclareMathOperator`.
+    /// Operators with `limits` take their sub- and superscripts above and
+    /// below like `\lim`.
+    DeclareMathOperator {
+        name: String,
+        definition: String,
+        limits: bool,
+    },
+    /// Declare a theorem-like environment with `\newtheorem`.
+    NewTheorem(TheoremDefinition),
+    /// Give a colour a name, with `\definecolor` (or `\colorlet` for named
+    /// colours and mixes).
+    DefineColor { name: String, color: Color },
+    /// Define a page style with `fancyhdr`'s `\fancypagestyle`.
+    PageStyle(Box<PageStyle>),
     /// An escape hatch for including an arbitrary bit of TeX in a preamble.
     UserDefined(String),
 }
@@ -263,30 +431,257 @@ impl Preamble {
     }
 
     /// Add a package import to the preamble.
+    ///
+    /// `amsmath` has to be loaded before `amsthm`, so if `amsthm` is already
+    /// imported `amsmath` goes just before it.
     pub fn use_package(&mut self, name: &str) -> &mut Self {
-        self.contents.push(PreambleElement::UsePackage {
+        let import = PreambleElement::UsePackage {
             package: name.to_string(),
             argument: None,
+        };
+
+        match self.package_position("amsthm") {
+            Some(i) if name == "amsmath" => self.contents.insert(i, import),
+            _ => self.contents.push(import),
+        }
+        self
+    }
+
+    /// Add a package import to the preamble, unless the package is already
+    /// being used.
+    pub fn require_package(&mut self, name: &str) -> &mut Self {
+        if !self.has_package(name) {
+            self.use_package(name);
+        }
+        self
+    }
+
+    /// Set the page layout by importing the `geometry` package with the
+    /// right options. If `geometry` is already imported its options are
+    /// replaced.
+    pub fn page_geometry(&mut self, geometry: PageGeometry) -> &mut Self {
+        let argument = if geometry.is_empty() {
+            None
+        } else {
+            Some(geometry.to_string())
+        };
+
+        for elem in &mut self.contents {
+            if let PreambleElement::UsePackage {
+                ref package,
+                argument: ref mut existing,
+            } = *elem
+            {
+                if package == "geometry" {
+                    *existing = argument;
+                    return self;
+                }
+            }
+        }
+
+        self.contents.push(PreambleElement::UsePackage {
+            package: String::from("geometry"),
+            argument,
         });
         self
     }
 
+    /// Does the preamble import a particular package?
+    ///
+    /// This also finds packages imported as part of a list (e.g.
+    /// `amsmath,amssymb`) and by a `\usepackage` written out in a
+    /// `PreambleElement::UserDefined`.
+    pub fn has_package(&self, name: &str) -> bool {
+        self.package_position(name).is_some()
+    }
+
+    fn package_position(&self, name: &str) -> Option<usize> {
+        self.contents
+            .iter()
+            .position(|elem| imported_packages(elem).contains(&name))
+    }
+
     /// Interface of most commonly used way to write a `/newcommand` line in latex.  
     /// If you want to create `/newcommand` in
     /// other ways(like add default argument or do not assign the num of arguments),
     /// please use `push` method in `Preamble` struct.
-    pub fn new_command(&mut self, name: &str, args_num: usize, definition: &str) -> &mut Self {
+    ///
+    /// The returned `Macro` can be used to call the new command.
+    pub fn new_command(&mut self, name: &str, args_num: usize, definition: &str) -> Macro {
         self.contents.push(PreambleElement::NewCommand {
             name: String::from(name),
             args_num: Some(args_num),
             default_arg: None,
             definition: String::from(definition),
         });
-        self
+        Macro::new(name, args_num, false)
+    }
+
+    /// Write a `\newcommand` whose first argument is optional, using
+    /// `default` when it's left out.
+    ///
+    /// This fails if the command doesn't take any arguments, since there's
+    /// nothing for the default to apply to.
+    pub fn new_command_with_default(
+        &mut self,
+        name: &str,
+        args_num: usize,
+        default: &str,
+        definition: &str,
+    ) -> Result<Macro, Error> {
+        if args_num == 0 {
+            bail!(
+                "\\{} doesn't take any arguments, so it can't have a default",
+                name
+            );
+        }
+
+        self.contents.push(PreambleElement::NewCommand {
+            name: String::from(name),
+            args_num: Some(args_num),
+            default_arg: Some(String::from(default)),
+            definition: String::from(definition),
+        });
+        Ok(Macro::new(name, args_num, true))
+    }
+
+    /// Redefine an existing command with `\renewcommand`.
+    pub fn renew_command(&mut self, name: &str, args_num: usize, definition: &str) -> Macro {
+        self.contents.push(PreambleElement::RenewCommand {
+            name: String::from(name),
+            args_num: Some(args_num),
+            default_arg: None,
+            definition: String::from(definition),
+        });
+        Macro::new(name, args_num, false)
+    }
+
+    /// Define a command with `\providecommand`, which leaves any existing
+    /// definition alone.
+    pub fn provide_command(&mut self, name: &str, args_num: usize, definition: &str) -> Macro {
+        self.contents.push(PreambleElement::ProvideCommand {
+            name: String::from(name),
+            args_num: Some(args_num),
+            default_arg: None,
+            definition: String::from(definition),
+        });
+        Macro::new(name, args_num, false)
+    }
+
+    /// Define a new environment, where `begin` and `end` are the code run at
+    /// `\begin{name}` and `\end{name}`.
+    ///
+    /// The returned `MacroEnvironment` creates `Environment`s which use it.
+    pub fn new_environment(
+        &mut self,
+        name: &str,
+        args_num: usize,
+        begin: &str,
+        end: &str,
+    ) -> MacroEnvironment {
+        self.contents.push(PreambleElement::NewEnvironment {
+            name: String::from(name),
+            args_num: Some(args_num),
+            default_arg: None,
+            begin: String::from(begin),
+            end: String::from(end),
+        });
+        MacroEnvironment::new(name, args_num, false)
+    }
+
+    /// Declare a math operator (e.g. `Tr` for the trace of a matrix),
+    /// importing `amsmath` if it isn't already being used.
+    ///
+    /// The operator only works in maths, so write it inside an
+    /// `InlineMath` or an `Equation` (e.g. `r"\Tr(A)"`). The returned
+    /// `Macro` is mostly useful for its name; the `Validator` reports an
+    /// error if it's called in normal text.
+    pub fn declare_math_operator(&mut self, name: &str, definition: &str) -> Macro {
+        self.push_math_operator(name, definition, false)
+    }
+
+    /// Declare a math operator which takes its sub- and superscripts above
+    /// and below it like `\lim`, with `\DeclareMathOperator*`.
+    pub fn declare_math_operator_with_limits(&mut self, name: &str, definition: &str) -> Macro {
+        self.push_math_operator(name, definition, true)
+    }
+
+    fn push_math_operator(&mut self, name: &str, definition: &str, limits: bool) -> Macro {
+        self.require_package("amsmath");
+        self.contents.push(PreambleElement::DeclareMathOperator {
+            name: String::from(name),
+            definition: String::from(definition),
+            limits,
+        });
+        Macro::new(name, 0, false)
+    }
+
+    /// Declare a theorem-like environment, importing `amsthm` if it isn't
+    /// already being used. `amsthm` is imported straight after `amsmath`,
+    /// which has to be loaded first.
+    pub fn new_theorem(&mut self, theorem: TheoremDefinition) -> &mut Self {
+        if !self.has_package("amsthm") {
+            let import = PreambleElement::UsePackage {
+                package: String::from("amsthm"),
+                argument: None,
+            };
+
+            match self.package_position("amsmath") {
+                Some(i) => self.contents.insert(i + 1, import),
+                None => self.contents.push(import),
+            }
+        }
+        self.push(theorem)
+    }
+
+    /// Give a colour a name, importing `xcolor` if it isn't already being
+    /// used.
+    ///
+    /// Defining the same colour twice only writes it out once, and
+    /// redefining a colour replaces the earlier definition. If the new
+    /// definition refers to a colour which is defined later on, it is moved
+    /// to the end so that colour exists by the time it is used. The returned
+    /// `Color` refers to the colour by name.
+    pub fn define_color(&mut self, name: &str, color: Color) -> Color {
+        self.require_package("xcolor");
+
+        let defines = |elem: &PreambleElement, wanted: &str| match *elem {
+            PreambleElement::DefineColor { ref name, .. } => name == wanted,
+            _ => false,
+        };
+        let existing = self.contents.iter().position(|elem| defines(elem, name));
+        let refers_to_later = existing.map_or(false, |i| {
+            let later = &self.contents[i + 1..];
+            color
+                .names()
+                .iter()
+                .any(|n| later.iter().any(|elem| defines(elem, n)))
+        });
+        let definition = PreambleElement::DefineColor {
+            name: name.to_string(),
+            color,
+        };
+
+        match existing {
+            Some(i) if refers_to_later => {
+                self.contents.remove(i);
+                self.contents.push(definition);
+            }
+            Some(i) => self.contents[i] = definition,
+            None => self.contents.push(definition),
+        }
+        Color::named(name)
+    }
+
+    /// Define a page style, importing `fancyhdr` if it isn't already being
+    /// used.
+    pub fn page_style(&mut self, style: PageStyle) -> &mut Self {
+        self.require_package("fancyhdr");
+        self.push(style)
     }
 
     /// Iterate over each package used in the Preamble.
-    pub fn iter(&self) -> Iter<PreambleElement> {
+    pub fn iter(&self) -> Iter<'_, PreambleElement> {
         self.contents.iter()
     }
 
@@ -309,6 +704,33 @@ impl Preamble {
     }
 }
 
+/// Every package a preamble element imports.
+fn imported_packages(element: &PreambleElement) -> Vec<&str> {
+    match *element {
+        PreambleElement::UsePackage { ref package, .. } => split_packages(package).collect(),
+        PreambleElement::UserDefined(ref raw) => raw
+            .split(r"\usepackage")
+            .skip(1)
+            .filter_map(|rest| {
+                // skip over any options to get to the `{...}` argument
+                let rest = rest.trim_start();
+                let rest = match rest.strip_prefix('[') {
+                    Some(options) => &options[options.find(']')? + 1..],
+                    None => rest,
+                };
+                let rest = rest.trim_start().strip_prefix('{')?;
+                Some(&rest[..rest.find('}')?])
+            })
+            .flat_map(split_packages)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn split_packages<'a>(list: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    list.split(',').map(str::trim).filter(|s| !s.is_empty())
+}
+
 impl Extend<PreambleElement> for Preamble {
     fn extend<T: IntoIterator<Item = PreambleElement>>(&mut self, iter: T) {
         for elem in iter {
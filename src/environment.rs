@@ -0,0 +1,100 @@
+use std::slice::Iter;
+
+use document::Element;
+use paragraph::ParagraphElement;
+
+/// A generic environment whose body is made up of other `Element`s.
+///
+/// This is for environments the crate doesn't have a dedicated type for,
+/// like `center`, `minipage` or `quote`. If you need the body to be passed
+/// through untouched (e.g. `verbatim`) use `Element::Environment` instead.
+///
+/// An `Environment` is added to a document as an `Element::Block`.
+///
+/// # Examples
+///
+/// ```rust
+/// use latex::{Environment, Length, List, ListKind};
+///
+/// let mut list = List::new(ListKind::Itemize);
+/// list.push("First").push("Second");
+///
+/// let mut minipage = Environment::new("minipage");
+/// minipage
+///     .optional("t")
+///     .argument(Length::text_width(0.5))
+///     .push("Some text")
+///     .push(list);
+///
+/// assert_eq!(
+///     minipage.to_string(),
+///     r"\begin{minipage}[t]{0.5\textwidth}
+/// Some text
+///
+/// \begin{itemize}
+/// \item First
+/// \item Second
+/// \end{itemize}
+/// \end{minipage}
+/// "
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Environment {
+    /// The environment's name.
+    pub name: String,
+    /// An optional `[...]` argument.
+    pub optional: Option<ParagraphElement>,
+    /// The mandatory `{...}` arguments, in order.
+    pub arguments: Vec<ParagraphElement>,
+    elements: Vec<Element>,
+}
+
+impl Environment {
+    /// Create an empty environment.
+    pub fn new(name: &str) -> Environment {
+        Environment {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the optional argument.
+    pub fn optional<P: Into<ParagraphElement>>(&mut self, arg: P) -> &mut Self {
+        self.optional = Some(arg.into());
+        self
+    }
+
+    /// Add a mandatory argument.
+    pub fn argument<P: Into<ParagraphElement>>(&mut self, arg: P) -> &mut Self {
+        self.arguments.push(arg.into());
+        self
+    }
+
+    /// Add an element to the environment's body.
+    pub fn push<E: Into<Element>>(&mut self, element: E) -> &mut Self {
+        self.elements.push(element.into());
+        self
+    }
+
+    /// Iterate over the elements in the environment's body.
+    pub fn iter(&self) -> Iter<'_, Element> {
+        self.elements.iter()
+    }
+
+    /// The elements in the environment's body, for rewriting them in place.
+    pub(crate) fn elements_mut(&mut self) -> &mut Vec<Element> {
+        &mut self.elements
+    }
+
+    /// Is the body empty?
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+}
+
+impl From<Environment> for Element {
+    fn from(other: Environment) -> Self {
+        Element::Block(other)
+    }
+}
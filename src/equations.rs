@@ -1,4 +1,4 @@
-use std::ops::Deref;
+use std::fmt::{self, Display, Formatter};
 use std::slice::Iter;
 
 /// A single equation.
@@ -32,6 +32,7 @@ use std::slice::Iter;
 pub struct Equation {
     text: String,
     label: Option<String>,
+    tag: Option<String>,
     not_numbered: bool,
 }
 
@@ -41,6 +42,7 @@ impl Equation {
         Equation {
             text: src.as_ref().to_string(),
             label: None,
+            tag: None,
             not_numbered: false,
         }
     }
@@ -60,6 +62,12 @@ impl Equation {
         self
     }
 
+    /// Use `\tag{...}` to give the equation a custom number.
+    pub fn tag(&mut self, tag: &str) -> &mut Self {
+        self.tag = Some(tag.to_string());
+        self
+    }
+
     /// Set the equation's text.
     pub fn text(&mut self, src: &str) -> &mut Self {
         self.text = src.to_string();
@@ -80,7 +88,12 @@ impl Equation {
 
     /// Get the equation label, if there is one.
     pub fn get_label(&self) -> Option<&str> {
-        self.label.as_ref().map(Deref::deref)
+        self.label.as_deref()
+    }
+
+    /// Get the equation's custom tag, if there is one.
+    pub fn get_tag(&self) -> Option<&str> {
+        self.tag.as_deref()
     }
 
     /// Is this equation numbered?
@@ -114,9 +127,11 @@ impl Equation {
 /// ```tex
 /// \begin{align}
 /// y &= mx + c \\
-/// E &= m c^2 \\
+/// E &= m c^2 \label{emc2}
 /// \end{align}
 /// ```
+///
+/// For environments other than `align`, see `DisplayMath`.
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Align {
     items: Vec<Equation>,
@@ -129,7 +144,7 @@ impl Align {
     }
 
     /// Iterate over each of this equations in the list.
-    pub fn iter(&self) -> Iter<Equation> {
+    pub fn iter(&self) -> Iter<'_, Equation> {
         self.items.iter()
     }
 
@@ -154,3 +169,268 @@ impl<'a> From<&'a str> for Align {
         eq
     }
 }
+
+/// The kind of display-math environment used by a `DisplayMath` block.
+///
+/// # Note
+///
+/// Everything except `Equation` requires the `amsmath` package.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MathEnvironment {
+    /// A single equation (`equation`).
+    Equation,
+    /// Several centred equations, each on its own line (`gather`).
+    Gather,
+    /// A single equation broken over several lines (`multline`).
+    Multline,
+    /// Several equations aligned at their `&`s (`align`).
+    Align,
+    /// Like `Align`, but with an explicit number of alignment columns
+    /// (`alignat{n}`).
+    Alignat(usize),
+}
+
+impl Default for MathEnvironment {
+    fn default() -> Self {
+        MathEnvironment::Align
+    }
+}
+
+impl MathEnvironment {
+    /// Get the `MathEnvironment`'s environment name.
+    pub fn environment_name(&self) -> &str {
+        match *self {
+            MathEnvironment::Equation => "equation",
+            MathEnvironment::Gather => "gather",
+            MathEnvironment::Multline => "multline",
+            MathEnvironment::Align => "align",
+            MathEnvironment::Alignat(_) => "alignat",
+        }
+    }
+}
+
+/// A block of display math, generalising `Align` to the other `amsmath`
+/// environments.
+///
+/// Each `Equation` pushed to the block becomes one line. Labels, `\tag`s and
+/// `\nonumber`s are emitted per line, except for `multline` where the whole
+/// block only gets a single number.
+///
+/// # Examples
+///
+/// ```rust
+/// use latex::{DisplayMath, Equation, MathEnvironment};
+///
+/// let mut eqs = DisplayMath::new(MathEnvironment::Gather);
+/// eqs.push(Equation::with_label("eq:first", "a = b"))
+///    .push("c = d");
+/// eqs.group_label("eq:both");
+/// ```
+///
+/// Which renders as:
+///
+/// ```tex
+/// \begin{subequations}
+/// \label{eq:both}
+/// \begin{gather}
+/// a = b \label{eq:first} \\
+/// c = d
+/// \end{gather}
+/// \end{subequations}
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DisplayMath {
+    /// The environment to use.
+    pub environment: MathEnvironment,
+    /// Are the lines numbered? Unnumbered blocks use the starred environment
+    /// (e.g. `align*`).
+    pub numbered: bool,
+    items: Vec<Equation>,
+    subequations: bool,
+    group_label: Option<String>,
+}
+
+impl DisplayMath {
+    /// Create an empty, numbered block of display math.
+    pub fn new(environment: MathEnvironment) -> DisplayMath {
+        DisplayMath {
+            environment,
+            numbered: true,
+            ..Default::default()
+        }
+    }
+
+    /// Create an empty block which uses the starred (unnumbered) environment.
+    pub fn unnumbered(environment: MathEnvironment) -> DisplayMath {
+        DisplayMath {
+            environment,
+            numbered: false,
+            ..Default::default()
+        }
+    }
+
+    /// Add a line to the end of the block.
+    pub fn push<E: Into<Equation>>(&mut self, eq: E) -> &mut Self {
+        self.items.push(eq.into());
+        self
+    }
+
+    /// Iterate over each of the lines in the block.
+    pub fn iter(&self) -> Iter<'_, Equation> {
+        self.items.iter()
+    }
+
+    /// Wrap the block in a `subequations` environment so its lines are
+    /// numbered (1a), (1b), ...
+    pub fn subequations(&mut self) -> &mut Self {
+        self.subequations = true;
+        self
+    }
+
+    /// Give the `subequations` group a label, implicitly enabling grouping.
+    pub fn group_label(&mut self, name: &str) -> &mut Self {
+        self.subequations = true;
+        self.group_label = Some(name.to_string());
+        self
+    }
+
+    /// Is this block wrapped in a `subequations` environment?
+    pub fn is_grouped(&self) -> bool {
+        self.subequations
+    }
+
+    /// Get the label of the `subequations` group, if there is one.
+    pub fn get_group_label(&self) -> Option<&str> {
+        self.group_label.as_deref()
+    }
+}
+
+impl From<Align> for DisplayMath {
+    fn from(other: Align) -> DisplayMath {
+        DisplayMath {
+            items: other.items,
+            ..DisplayMath::new(MathEnvironment::Align)
+        }
+    }
+}
+
+impl From<Equation> for DisplayMath {
+    /// Wrap a single equation in an `equation` environment.
+    fn from(other: Equation) -> DisplayMath {
+        let mut math = DisplayMath::new(MathEnvironment::Equation);
+        math.push(other);
+        math
+    }
+}
+
+/// A `split` environment, for breaking a single equation over several
+/// aligned lines.
+///
+/// Like a `Matrix`, this is just math-mode text and is normally nested inside
+/// an `equation` by converting it into an `Equation`.
+///
+/// ```rust
+/// use latex::{DisplayMath, Equation, Split};
+///
+/// let mut split = Split::new();
+/// split.push("(a + b)^2 &= (a + b)(a + b)")
+///      .push("&= a^2 + 2ab + b^2");
+///
+/// let mut eq = Equation::from(split);
+/// eq.label("eq:binomial");
+/// let math = DisplayMath::from(eq);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Split {
+    lines: Vec<String>,
+}
+
+impl Split {
+    /// Create an empty `split`.
+    pub fn new() -> Split {
+        Default::default()
+    }
+
+    /// Add a line to the `split`.
+    pub fn push<S: AsRef<str>>(&mut self, line: S) -> &mut Self {
+        self.lines.push(line.as_ref().to_string());
+        self
+    }
+
+    /// Iterate over the lines in the `split`.
+    pub fn iter(&self) -> Iter<'_, String> {
+        self.lines.iter()
+    }
+}
+
+impl Display for Split {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, r"\begin{{split}}")?;
+        writeln!(f, r"{}", self.lines.join(" \\\\\n"))?;
+        write!(f, r"\end{{split}}")
+    }
+}
+
+impl From<Split> for Equation {
+    fn from(other: Split) -> Equation {
+        Equation::new(other.to_string())
+    }
+}
+
+/// A `cases` environment, for piecewise definitions.
+///
+/// ```rust
+/// use latex::{Cases, Equation};
+///
+/// let mut cases = Cases::new();
+/// cases.push("x", r"x \geq 0").push("-x", r"\text{otherwise}");
+///
+/// let eq = Equation::new(format!("|x| = {}", cases));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Cases {
+    rows: Vec<(String, String)>,
+}
+
+impl Cases {
+    /// Create an empty `cases` environment.
+    pub fn new() -> Cases {
+        Default::default()
+    }
+
+    /// Add a value and the condition under which it applies.
+    pub fn push<V, C>(&mut self, value: V, condition: C) -> &mut Self
+    where
+        V: AsRef<str>,
+        C: AsRef<str>,
+    {
+        self.rows
+            .push((value.as_ref().to_string(), condition.as_ref().to_string()));
+        self
+    }
+
+    /// Iterate over the `(value, condition)` pairs.
+    pub fn iter(&self) -> Iter<'_, (String, String)> {
+        self.rows.iter()
+    }
+}
+
+impl Display for Cases {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let rows: Vec<String> = self
+            .rows
+            .iter()
+            .map(|(value, condition)| format!("{} & {}", value, condition))
+            .collect();
+
+        writeln!(f, r"\begin{{cases}}")?;
+        writeln!(f, r"{}", rows.join(" \\\\\n"))?;
+        write!(f, r"\end{{cases}}")
+    }
+}
+
+impl From<Cases> for Equation {
+    fn from(other: Cases) -> Equation {
+        Equation::new(other.to_string())
+    }
+}
@@ -0,0 +1,252 @@
+use std::fmt::{self, Display, Formatter};
+
+use length::Length;
+use options::{ClassOption, PaperSize};
+
+/// Which way up the paper is.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[allow(missing_docs)]
+pub enum Orientation {
+    Portrait,
+    Landscape,
+}
+
+/// The page layout, written as options for the `geometry` package.
+///
+/// Anything left unset keeps `geometry`'s default.
+///
+/// # Examples
+///
+/// ```rust
+/// use latex::{Document, DocumentClass, Length, PageGeometry, PaperSize};
+///
+/// let mut geometry = PageGeometry::new();
+/// geometry
+///     .paper(PaperSize::A4)
+///     .margin(Length::cm(2.5))
+///     .left(Length::cm(3.0))
+///     .binding_offset(Length::mm(5.0));
+///
+/// let mut doc = Document::new(DocumentClass::Report);
+/// doc.page_geometry(geometry);
+///
+/// let rendered = latex::print(&doc).unwrap();
+/// assert!(rendered.contains(
+///     r"\usepackage[a4paper,top=2.5cm,bottom=2.5cm,left=3cm,right=2.5cm,bindingoffset=5mm]{geometry}"
+/// ));
+/// ```
+///
+/// The layout can be changed part way through a document (e.g. for a wide
+/// table) and changed back again afterwards.
+///
+/// ```rust
+/// use latex::{Document, DocumentClass, Element, Length, PageGeometry};
+///
+/// let mut wide = PageGeometry::new();
+/// wide.margin(Length::cm(1.0));
+///
+/// let mut doc = Document::new(DocumentClass::Article);
+/// doc.push(Element::NewGeometry(wide))
+///     .push("Something wide")
+///     .push(Element::RestoreGeometry);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PageGeometry {
+    /// The paper size.
+    pub paper: Option<PaperSize>,
+    /// The paper's orientation.
+    pub orientation: Option<Orientation>,
+    /// The top margin.
+    pub top: Option<Length>,
+    /// The bottom margin.
+    pub bottom: Option<Length>,
+    /// The left (or inner, for two-sided documents) margin.
+    pub left: Option<Length>,
+    /// The right (or outer, for two-sided documents) margin.
+    pub right: Option<Length>,
+    /// Extra space on the inner edge which is lost to the binding.
+    pub binding_offset: Option<Length>,
+    /// The height of the header.
+    pub head_height: Option<Length>,
+    /// The gap between the header and the body.
+    pub head_sep: Option<Length>,
+    /// The distance from the bottom of the body to the bottom of the footer.
+    pub foot_skip: Option<Length>,
+}
+
+impl PageGeometry {
+    /// Create a `PageGeometry` which doesn't change anything.
+    pub fn new() -> PageGeometry {
+        Default::default()
+    }
+
+    /// Set the paper size.
+    pub fn paper(&mut self, paper: PaperSize) -> &mut Self {
+        self.paper = Some(paper);
+        self
+    }
+
+    /// Turn the page on its side.
+    pub fn landscape(&mut self) -> &mut Self {
+        self.orientation = Some(Orientation::Landscape);
+        self
+    }
+
+    /// Use the page the normal way up.
+    pub fn portrait(&mut self) -> &mut Self {
+        self.orientation = Some(Orientation::Portrait);
+        self
+    }
+
+    /// Set all four margins at once.
+    pub fn margin(&mut self, margin: Length) -> &mut Self {
+        self.top = Some(margin.clone());
+        self.bottom = Some(margin.clone());
+        self.left = Some(margin.clone());
+        self.right = Some(margin);
+        self
+    }
+
+    /// Set the top margin.
+    pub fn top(&mut self, margin: Length) -> &mut Self {
+        self.top = Some(margin);
+        self
+    }
+
+    /// Set the bottom margin.
+    pub fn bottom(&mut self, margin: Length) -> &mut Self {
+        self.bottom = Some(margin);
+        self
+    }
+
+    /// Set the left margin.
+    pub fn left(&mut self, margin: Length) -> &mut Self {
+        self.left = Some(margin);
+        self
+    }
+
+    /// Set the right margin.
+    pub fn right(&mut self, margin: Length) -> &mut Self {
+        self.right = Some(margin);
+        self
+    }
+
+    /// Set the binding offset.
+    pub fn binding_offset(&mut self, offset: Length) -> &mut Self {
+        self.binding_offset = Some(offset);
+        self
+    }
+
+    /// Set the header's height.
+    pub fn head_height(&mut self, height: Length) -> &mut Self {
+        self.head_height = Some(height);
+        self
+    }
+
+    /// Set the gap between the header and the body.
+    pub fn head_sep(&mut self, sep: Length) -> &mut Self {
+        self.head_sep = Some(sep);
+        self
+    }
+
+    /// Set the distance from the body to the bottom of the footer.
+    pub fn foot_skip(&mut self, skip: Length) -> &mut Self {
+        self.foot_skip = Some(skip);
+        self
+    }
+
+    /// Does this set the paper size or orientation? Those can only be set in
+    /// the preamble, so `\newgeometry` ignores them.
+    pub fn sets_paper(&self) -> bool {
+        self.paper.is_some() || self.orientation.is_some()
+    }
+
+    /// Is there nothing to set?
+    pub fn is_empty(&self) -> bool {
+        *self == PageGeometry::default()
+    }
+
+    /// The options for everything except the paper, in the form
+    /// `\newgeometry` expects.
+    pub fn layout_options(&self) -> Vec<String> {
+        let lengths = [
+            ("top", &self.top),
+            ("bottom", &self.bottom),
+            ("left", &self.left),
+            ("right", &self.right),
+            ("bindingoffset", &self.binding_offset),
+            ("headheight", &self.head_height),
+            ("headsep", &self.head_sep),
+            ("footskip", &self.foot_skip),
+        ];
+
+        lengths
+            .iter()
+            .filter_map(|&(key, length)| length.as_ref().map(|l| format!("{}={}", key, l)))
+            .collect()
+    }
+
+    /// Every option to pass to the `geometry` package.
+    pub fn options(&self) -> Vec<String> {
+        let mut options = Vec::new();
+
+        if let Some(paper) = self.paper {
+            options.push(ClassOption::Paper(paper).to_string());
+        }
+        match self.orientation {
+            Some(Orientation::Portrait) => options.push(String::from("portrait")),
+            Some(Orientation::Landscape) => options.push(String::from("landscape")),
+            None => {}
+        }
+
+        options.extend(self.layout_options());
+        options
+    }
+}
+
+impl Display for PageGeometry {
+    /// Write the options as a comma-separated list.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.options().join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_geometry_has_no_options() {
+        let geometry = PageGeometry::new();
+
+        assert!(geometry.is_empty());
+        assert_eq!(geometry.to_string(), "");
+    }
+
+    #[test]
+    fn options_are_written_in_a_fixed_order() {
+        let mut geometry = PageGeometry::new();
+        geometry
+            .foot_skip(Length::cm(1.0))
+            .head_height(Length::pt(14.0))
+            .top(Length::inches(1.0))
+            .landscape()
+            .paper(PaperSize::Letter);
+
+        assert_eq!(
+            geometry.options(),
+            vec![
+                "letterpaper",
+                "landscape",
+                "top=1in",
+                "headheight=14pt",
+                "footskip=1cm"
+            ]
+        );
+        assert_eq!(
+            geometry.layout_options(),
+            vec!["top=1in", "headheight=14pt", "footskip=1cm"]
+        );
+        assert!(geometry.sets_paper());
+    }
+}
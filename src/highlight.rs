@@ -0,0 +1,128 @@
+//! Syntax highlighting which runs entirely in Rust, for build machines where
+//! `minted` (and therefore `-shell-escape` and Pygments) isn't an option.
+
+use std::sync::OnceLock;
+
+use failure::Error;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Color, FontStyle, Style, Theme, ThemeSet};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+/// The bundled theme used for highlighting. It has a white background, so it
+/// works well on paper.
+pub const THEME: &str = "InspiredGitHub";
+
+fn syntaxes() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn theme() -> &'static Theme {
+    static THEMES: OnceLock<ThemeSet> = OnceLock::new();
+    &THEMES.get_or_init(ThemeSet::load_defaults).themes[THEME]
+}
+
+/// Highlight some code, returning each line as `tex` suitable for a
+/// `Verbatim` environment with `commandchars=\\\{\}`.
+///
+/// The language can be either a name (`"Rust"`) or a file extension
+/// (`"rs"`). Unknown languages are left as plain text.
+///
+/// # Examples
+///
+/// ```rust
+/// let lines = latex::highlight("let x = 1;\nx + 1", Some("rs")).unwrap();
+///
+/// assert_eq!(lines.len(), 2);
+/// assert!(lines[0].starts_with(r"\textcolor[RGB]{167,29,93}{\textbf{let}}"));
+/// ```
+pub fn highlight(code: &str, language: Option<&str>) -> Result<Vec<String>, Error> {
+    let syntaxes = syntaxes();
+    let theme = theme();
+    let syntax = language
+        .and_then(|lang| syntaxes.find_syntax_by_token(lang))
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+    let foreground = theme.settings.foreground;
+
+    let mut highlighter = HighlightLines::new(syntax, theme);
+    let mut lines = Vec::new();
+
+    // a stray `\r` would end up in the output, so CRLF line endings are
+    // normalised first
+    let code = code.replace("\r\n", "\n");
+    for line in LinesWithEndings::from(code.trim_end_matches('\n')) {
+        let mut rendered = String::new();
+        for (style, text) in highlighter.highlight_line(line, syntaxes)? {
+            write_token(
+                &mut rendered,
+                style,
+                text.trim_end_matches('\n'),
+                foreground,
+            );
+        }
+        lines.push(rendered);
+    }
+
+    Ok(lines)
+}
+
+fn write_token(buffer: &mut String, style: Style, text: &str, foreground: Option<Color>) {
+    let text = escape(text);
+    if text.trim().is_empty() {
+        buffer.push_str(&text);
+        return;
+    }
+
+    let mut token = text;
+    if style.font_style.contains(FontStyle::BOLD) {
+        token = format!(r"\textbf{{{}}}", token);
+    }
+    if style.font_style.contains(FontStyle::ITALIC) {
+        token = format!(r"\textit{{{}}}", token);
+    }
+    if Some(style.foreground) != foreground {
+        let Color { r, g, b, .. } = style.foreground;
+        token = format!(r"\textcolor[RGB]{{{},{},{}}}{{{}}}", r, g, b, token);
+    }
+
+    buffer.push_str(&token);
+}
+
+/// Escape the characters which `commandchars=\\\{\}` makes special.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str(r"\char92{}"),
+            '{' => escaped.push_str(r"\char123{}"),
+            '}' => escaped.push_str(r"\char125{}"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_command_characters() {
+        assert_eq!(escape(r"{\n}"), r"\char123{}\char92{}n\char125{}");
+    }
+
+    #[test]
+    fn unknown_languages_are_plain_text() {
+        let lines = highlight("a {b}\nc\n", Some("not-a-language")).unwrap();
+
+        assert_eq!(lines, vec![r"a \char123{}b\char125{}", "c"]);
+    }
+
+    #[test]
+    fn windows_line_endings() {
+        let lines = highlight("a\r\nb\r\n", None).unwrap();
+
+        assert_eq!(lines, vec!["a", "b"]);
+    }
+}
@@ -0,0 +1,387 @@
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+
+use failure::Error;
+use paragraph::ParagraphElement;
+
+/// The units a `Length` can be measured in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LengthUnit {
+    /// Points (`pt`), 1/72.27 of an inch.
+    Pt,
+    /// Millimetres (`mm`).
+    Mm,
+    /// Centimetres (`cm`).
+    Cm,
+    /// Inches (`in`).
+    In,
+    /// The width of an "M" in the current font (`em`).
+    Em,
+    /// The height of an "x" in the current font (`ex`).
+    Ex,
+    /// The width of the text block (`\textwidth`).
+    TextWidth,
+    /// The width of the current line (`\linewidth`).
+    LineWidth,
+}
+
+impl LengthUnit {
+    /// The suffix written after a number in this unit.
+    pub fn suffix(&self) -> &'static str {
+        match *self {
+            LengthUnit::Pt => "pt",
+            LengthUnit::Mm => "mm",
+            LengthUnit::Cm => "cm",
+            LengthUnit::In => "in",
+            LengthUnit::Em => "em",
+            LengthUnit::Ex => "ex",
+            LengthUnit::TextWidth => r"\textwidth",
+            LengthUnit::LineWidth => r"\linewidth",
+        }
+    }
+}
+
+impl FromStr for LengthUnit {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<LengthUnit, Error> {
+        let unit = match s.trim() {
+            "pt" => LengthUnit::Pt,
+            "mm" => LengthUnit::Mm,
+            "cm" => LengthUnit::Cm,
+            "in" => LengthUnit::In,
+            "em" => LengthUnit::Em,
+            "ex" => LengthUnit::Ex,
+            r"\textwidth" => LengthUnit::TextWidth,
+            r"\linewidth" => LengthUnit::LineWidth,
+            other => bail!("Unknown length unit, \"{}\"", other),
+        };
+
+        Ok(unit)
+    }
+}
+
+/// A dimension, like `12pt` or `0.5\textwidth`.
+///
+/// Lengths can be added, subtracted and scaled. Adding two lengths in the
+/// same unit just adds the numbers, while a length made up of several units
+/// is written using `\dimexpr` so TeX can work it out.
+///
+/// # Examples
+///
+/// ```rust
+/// use latex::Length;
+///
+/// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let margin = Length::cm(1.5) + Length::cm(1.0);
+/// assert_eq!(margin.to_string(), "2.5cm");
+///
+/// let width = Length::text_width(0.5) - Length::em(1.0) * 2.0;
+/// assert_eq!(width.to_string(), r"\dimexpr 0.5\textwidth - 2em\relax");
+///
+/// let parsed = r"0.5\textwidth - 2em"
+///     .parse::<Length>()
+///     .map_err(|e| e.compat())?;
+/// assert_eq!(parsed, width);
+/// # Ok(())
+/// # }
+/// # fn main() {
+/// # run().unwrap();
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Length {
+    /// Each unit used in the length and how many of it there are. A unit
+    /// appears at most once and never with a zero amount.
+    terms: Vec<(f64, LengthUnit)>,
+}
+
+impl Length {
+    /// Create a length in a particular unit.
+    pub fn new(value: f64, unit: LengthUnit) -> Length {
+        let mut length = Length::zero();
+        length.add_term(value, unit);
+        length
+    }
+
+    /// A length of nothing, written as `0pt`.
+    pub fn zero() -> Length {
+        Default::default()
+    }
+
+    /// A length in points.
+    pub fn pt(value: f64) -> Length {
+        Length::new(value, LengthUnit::Pt)
+    }
+
+    /// A length in millimetres.
+    pub fn mm(value: f64) -> Length {
+        Length::new(value, LengthUnit::Mm)
+    }
+
+    /// A length in centimetres.
+    pub fn cm(value: f64) -> Length {
+        Length::new(value, LengthUnit::Cm)
+    }
+
+    /// A length in inches.
+    pub fn inches(value: f64) -> Length {
+        Length::new(value, LengthUnit::In)
+    }
+
+    /// A length relative to the font's `em`.
+    pub fn em(value: f64) -> Length {
+        Length::new(value, LengthUnit::Em)
+    }
+
+    /// A length relative to the font's `ex`.
+    pub fn ex(value: f64) -> Length {
+        Length::new(value, LengthUnit::Ex)
+    }
+
+    /// A fraction of `\textwidth`.
+    pub fn text_width(fraction: f64) -> Length {
+        Length::new(fraction, LengthUnit::TextWidth)
+    }
+
+    /// A fraction of `\linewidth`.
+    pub fn line_width(fraction: f64) -> Length {
+        Length::new(fraction, LengthUnit::LineWidth)
+    }
+
+    /// Is this length zero?
+    pub fn is_zero(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Is this length made up of more than one unit (and therefore needs
+    /// `\dimexpr`)?
+    pub fn is_mixed(&self) -> bool {
+        self.terms.len() > 1
+    }
+
+    /// Iterate over the amount of each unit in this length.
+    pub fn terms(&self) -> impl Iterator<Item = (f64, LengthUnit)> + '_ {
+        self.terms.iter().cloned()
+    }
+
+    fn add_term(&mut self, value: f64, unit: LengthUnit) {
+        match self.terms.iter().position(|&(_, u)| u == unit) {
+            Some(i) => self.terms[i].0 += value,
+            None => self.terms.push((value, unit)),
+        }
+        self.terms.retain(|&(v, _)| round(v) != 0.0);
+    }
+}
+
+/// Round off floating point noise (e.g. `0.1 + 0.2`) before printing. TeX
+/// doesn't care about anything past the 5th decimal place anyway.
+fn round(value: f64) -> f64 {
+    (value * 100_000.0).round() / 100_000.0
+}
+
+fn write_term(f: &mut Formatter<'_>, value: f64, unit: LengthUnit) -> fmt::Result {
+    let value = round(value);
+
+    match unit {
+        // "1\textwidth" is just "\textwidth"
+        LengthUnit::TextWidth | LengthUnit::LineWidth if value == 1.0 => {
+            write!(f, "{}", unit.suffix())
+        }
+        _ => write!(f, "{}{}", value, unit.suffix()),
+    }
+}
+
+impl Display for Length {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.terms.len() {
+            0 => write!(f, "0pt"),
+            1 => write_term(f, self.terms[0].0, self.terms[0].1),
+            _ => {
+                write!(f, r"\dimexpr ")?;
+                for (i, &(value, unit)) in self.terms.iter().enumerate() {
+                    if i == 0 {
+                        write_term(f, value, unit)?;
+                    } else if value < 0.0 {
+                        write!(f, " - ")?;
+                        write_term(f, -value, unit)?;
+                    } else {
+                        write!(f, " + ")?;
+                        write_term(f, value, unit)?;
+                    }
+                }
+                write!(f, r"\relax")
+            }
+        }
+    }
+}
+
+impl FromStr for Length {
+    type Err = Error;
+
+    /// Parse a length such as `12pt`, `-1.5 cm`, `\linewidth` or a sum like
+    /// `0.5\textwidth - 2em` (optionally wrapped in `\dimexpr ... \relax`).
+    fn from_str(s: &str) -> Result<Length, Error> {
+        let mut src = s.trim();
+        if src.starts_with(r"\dimexpr") && src.ends_with(r"\relax") {
+            src = src[r"\dimexpr".len()..src.len() - r"\relax".len()].trim();
+        }
+        if src.is_empty() {
+            bail!("Can't parse an empty string as a length");
+        }
+
+        let mut length = Length::zero();
+        let mut sign = 1.0;
+        let mut term = String::new();
+
+        for c in src.chars().chain(Some('+')) {
+            if (c == '+' || c == '-') && !term.trim().is_empty() {
+                let (value, unit) = parse_term(term.trim())?;
+                length.add_term(sign * value, unit);
+                term.clear();
+                sign = if c == '-' { -1.0 } else { 1.0 };
+            } else if c == '-' {
+                sign = -sign;
+            } else if c != '+' {
+                term.push(c);
+            }
+        }
+
+        Ok(length)
+    }
+}
+
+/// Parse a single term like `1.5cm` or `0.5\textwidth`.
+fn parse_term(term: &str) -> Result<(f64, LengthUnit), Error> {
+    let split = match term.find('\\') {
+        Some(i) => i,
+        None => term
+            .find(|c: char| c.is_ascii_alphabetic())
+            .unwrap_or(term.len()),
+    };
+    let (number, unit) = term.split_at(split);
+    let number = number.trim();
+
+    let value = if number.is_empty() && unit.starts_with('\\') {
+        1.0
+    } else {
+        match number.parse() {
+            Ok(value) => value,
+            Err(_) => bail!("\"{}\" isn't a valid length", term),
+        }
+    };
+
+    Ok((value, unit.parse()?))
+}
+
+impl Add for Length {
+    type Output = Length;
+
+    fn add(mut self, other: Length) -> Length {
+        for (value, unit) in other.terms {
+            self.add_term(value, unit);
+        }
+        self
+    }
+}
+
+impl Sub for Length {
+    type Output = Length;
+
+    fn sub(self, other: Length) -> Length {
+        self + -other
+    }
+}
+
+impl Neg for Length {
+    type Output = Length;
+
+    fn neg(self) -> Length {
+        self * -1.0
+    }
+}
+
+impl Mul<f64> for Length {
+    type Output = Length;
+
+    fn mul(mut self, factor: f64) -> Length {
+        for term in &mut self.terms {
+            term.0 *= factor;
+        }
+        self.terms.retain(|&(v, _)| round(v) != 0.0);
+        self
+    }
+}
+
+impl From<Length> for ParagraphElement {
+    /// Write a length as-is, e.g. when passing it as an argument to a
+    /// `Command` or an `Environment`.
+    fn from(other: Length) -> Self {
+        ParagraphElement::Plain(other.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_simple_lengths() {
+        let inputs = vec![
+            (Length::pt(12.0), "12pt"),
+            (Length::mm(-3.0), "-3mm"),
+            (Length::cm(1.5), "1.5cm"),
+            (Length::inches(1.0), "1in"),
+            (Length::ex(0.5), "0.5ex"),
+            (Length::text_width(1.0), r"\textwidth"),
+            (Length::line_width(0.25), r"0.25\linewidth"),
+            (Length::zero(), "0pt"),
+            (Length::cm(0.1) + Length::cm(0.2), "0.3cm"),
+        ];
+
+        for (length, should_be) in inputs {
+            assert_eq!(length.to_string(), should_be);
+        }
+    }
+
+    #[test]
+    fn arithmetic_combines_units() {
+        let got = Length::em(1.0) + Length::pt(2.0) + Length::em(1.0) - Length::pt(2.0);
+        assert_eq!(got, Length::em(2.0));
+        assert!(!got.is_mixed());
+
+        let got = (Length::line_width(1.0) - Length::cm(1.0)) * 0.5;
+        assert_eq!(got.to_string(), r"\dimexpr 0.5\linewidth - 0.5cm\relax");
+        assert!(got.is_mixed());
+
+        assert!((Length::mm(3.0) - Length::mm(3.0)).is_zero());
+    }
+
+    #[test]
+    fn parse_lengths() {
+        let inputs = vec![
+            ("12pt", Length::pt(12.0)),
+            (" -1.5 cm ", Length::cm(-1.5)),
+            (r"\linewidth", Length::line_width(1.0)),
+            (r"0.3\textwidth", Length::text_width(0.3)),
+            (
+                r"\dimexpr \textwidth - 2em + 1ex\relax",
+                Length::text_width(1.0) - Length::em(2.0) + Length::ex(1.0),
+            ),
+            ("1in + -1in", Length::zero()),
+        ];
+
+        for (src, should_be) in inputs {
+            let got: Length = src.parse().unwrap();
+            assert_eq!(got, should_be, "{:?}", src);
+        }
+    }
+
+    #[test]
+    fn invalid_lengths() {
+        for src in ["", "12", "pt", "1.2.3cm", "3furlongs", r"2\parskip"] {
+            assert!(src.parse::<Length>().is_err(), "{:?}", src);
+        }
+    }
+}
@@ -104,19 +104,57 @@
 
 #![deny(missing_docs)]
 
+#[macro_use]
 extern crate failure;
+#[cfg(feature = "highlight")]
+extern crate syntect;
 
+mod code;
+mod color;
+mod command;
 mod document;
+mod environment;
 mod equations;
+mod geometry;
+// syntect needs a much newer compiler than the rest of the crate anyway
+#[cfg(feature = "highlight")]
+#[clippy::msrv = "1.70"]
+mod highlight;
+mod length;
 mod lists;
+mod matrix;
+mod options;
+mod outline;
+mod page_style;
 mod paragraph;
 mod section;
+mod theorem;
 mod visitor;
 
+pub use code::{CodeBackend, CodeBlock};
+pub use color::Color;
+pub use command::{Argument, Command, Macro, MacroEnvironment};
 pub use document::{Document, DocumentClass, Element, Preamble, PreambleElement};
-pub use equations::{Align, Equation};
-pub use lists::{Item, List, ListKind};
-pub use paragraph::{Paragraph, ParagraphElement};
-pub use section::{Chapter, Part, Section, SectionElement, Subsection, Subsubsection};
+pub use environment::Environment;
+pub use equations::{Align, Cases, DisplayMath, Equation, MathEnvironment, Split};
+pub use geometry::{Orientation, PageGeometry};
+#[cfg(feature = "highlight")]
+pub use highlight::highlight;
+pub use length::{Length, LengthUnit};
+pub use lists::{CheckboxStyle, Item, List, ListKind, ListOptions, Numbering};
+pub use matrix::{Matrix, MatrixDelimiter, NumberFormat};
+pub use options::{ClassOption, PaperSize, PointSize};
+pub use outline::{Outline, OutlineBuilder, OutlineEvent, OutlineWarning};
+pub use page_style::{HeaderFooter, PageStyle};
+pub use paragraph::{FontSize, Paragraph, ParagraphElement};
+pub use section::{
+    shift_headings, Chapter, LevelOverflow, ParagraphSection, Part, Section, SectionElement,
+    Subparagraph, Subsection, Subsubsection,
+};
+pub use theorem::{Proof, Theorem, TheoremDefinition, TheoremStyle};
 
-pub use visitor::{print, Visitor};
+pub use visitor::{
+    alignment_points, check_alignment, print, summarise_checklists, validate, AlignmentChecker,
+    AlignmentIssue, AlignmentProblem, ChecklistSummary, Diagnostic, FmtWriter, Printer, Severity,
+    Validator, Visitor,
+};
@@ -1,16 +1,185 @@
-use std::ops::Deref;
-use std::slice::Iter;
+use std::fmt::{self, Display, Formatter};
+use std::slice::{Iter, IterMut};
 
-/// Wrapper around a single list item.
+use document::Element;
+use failure::Error;
+use paragraph::{Paragraph, ParagraphElement};
+use visitor::{Printer, Visitor};
+
+/// A single list item.
 ///
 /// An item will usually be rendered with `\item` followed by the item's text.
-#[derive(Clone, Debug, PartialEq)]
-pub struct Item(pub String);
+/// Any other elements (further paragraphs, equations or a nested `List`) are
+/// rendered after the text, inside the same item.
+///
+/// # Examples
+///
+/// ```rust
+/// use latex::{Item, List, ListKind, Paragraph, ParagraphElement};
+///
+/// let mut text = Paragraph::new();
+/// text.push("Read the ").push(ParagraphElement::bold("manual"));
+///
+/// let mut steps = List::new(ListKind::Enumerate);
+/// steps.push("Install").push("Configure");
+///
+/// let mut item = Item::new(text);
+/// item.push(steps);
+///
+/// let mut list = List::new(ListKind::Itemize);
+/// list.push(item).push("Done!");
+/// ```
+///
+/// Which renders as:
+///
+/// ```tex
+/// \begin{itemize}
+/// \item Read the \textbf{manual}
+///   \begin{enumerate}
+///   \item Install
+///   \item Configure
+///   \end{enumerate}
+/// \item Done!
+/// \end{itemize}
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Item {
+    /// A custom label (`\item[label]`), which is also the term being
+    /// described in a `Description` list.
+    pub label: Option<ParagraphElement>,
+    /// Whether the task is done, for items in a checklist.
+    pub checked: Option<bool>,
+    /// The text written straight after `\item`.
+    pub content: Paragraph,
+    elements: Vec<Element>,
+}
+
+impl Item {
+    /// Create a new item with the specified text.
+    pub fn new<P: Into<Paragraph>>(content: P) -> Item {
+        Item {
+            label: None,
+            checked: None,
+            content: content.into(),
+            elements: Vec::new(),
+        }
+    }
+
+    /// Create a new item with a custom label, or a term and its description
+    /// when used in a `Description` list.
+    ///
+    /// A label containing a `,` or `]` is wrapped in braces so it doesn't
+    /// end the optional argument early.
+    ///
+    /// ```rust
+    /// use latex::{Item, List, ListKind, ParagraphElement};
+    ///
+    /// let mut glossary = List::new(ListKind::Description);
+    /// glossary.push(Item::labelled("AST", "Abstract Syntax Tree"));
+    ///
+    /// let mut stars = List::new(ListKind::Itemize);
+    /// stars.push(Item::labelled(ParagraphElement::InlineMath(r"\star".into()), "Shiny"));
+    /// ```
+    pub fn labelled<L, P>(label: L, content: P) -> Item
+    where
+        L: Into<ParagraphElement>,
+        P: Into<Paragraph>,
+    {
+        let mut item = Item::new(content);
+        item.label = Some(label.into());
+        item
+    }
+
+    /// Create a checklist item which is either done or not done.
+    pub fn task<P: Into<Paragraph>>(done: bool, content: P) -> Item {
+        let mut item = Item::new(content);
+        item.checked = Some(done);
+        item
+    }
+
+    /// Add an element (e.g. a nested `List`) to the item.
+    pub fn push<E>(&mut self, element: E) -> &mut Self
+    where
+        E: Into<Element>,
+    {
+        self.elements.push(element.into());
+        self
+    }
+
+    /// Iterate over the elements which follow the item's text.
+    pub fn iter(&self) -> Iter<'_, Element> {
+        self.elements.iter()
+    }
+
+    /// The elements which follow the item's text, for rewriting them in
+    /// place.
+    pub(crate) fn elements_mut(&mut self) -> &mut Vec<Element> {
+        &mut self.elements
+    }
+
+    /// Is the item drawn with a checkbox? Tasks always are, and so is
+    /// every item in a checklist, unless it has its own label.
+    pub(crate) fn has_checkbox(&self, in_checklist: bool) -> bool {
+        self.label.is_none() && (self.checked.is_some() || in_checklist)
+    }
+}
 
-impl Deref for Item {
-    type Target = str;
-    fn deref(&self) -> &Self::Target {
-        &self.0
+impl<'a> From<&'a str> for Item {
+    fn from(other: &'a str) -> Item {
+        Item::new(other)
+    }
+}
+
+impl From<String> for Item {
+    fn from(other: String) -> Item {
+        Item::new(other.as_str())
+    }
+}
+
+impl From<Paragraph> for Item {
+    fn from(other: Paragraph) -> Item {
+        Item::new(other)
+    }
+}
+
+impl From<ParagraphElement> for Item {
+    fn from(other: ParagraphElement) -> Item {
+        Item::new(other)
+    }
+}
+
+/// The symbols used for the boxes in a checklist.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CheckboxStyle {
+    /// `$\square$` and `$\boxtimes$` from the `amssymb` package.
+    Amssymb,
+    /// `\ding{113}` and `\ding{52}` from the `pifont` package.
+    Pifont,
+}
+
+impl Default for CheckboxStyle {
+    fn default() -> Self {
+        CheckboxStyle::Amssymb
+    }
+}
+
+impl CheckboxStyle {
+    /// The package which provides the symbols.
+    pub fn package(&self) -> &str {
+        match *self {
+            CheckboxStyle::Amssymb => "amssymb",
+            CheckboxStyle::Pifont => "pifont",
+        }
+    }
+
+    /// The symbol used for an item which is (or isn't) done.
+    pub fn symbol(&self, done: bool) -> &str {
+        match (*self, done) {
+            (CheckboxStyle::Amssymb, false) => r"$\square$",
+            (CheckboxStyle::Amssymb, true) => r"$\boxtimes$",
+            (CheckboxStyle::Pifont, false) => r"\ding{113}",
+            (CheckboxStyle::Pifont, true) => r"\ding{52}",
+        }
     }
 }
 
@@ -21,6 +190,15 @@ pub enum ListKind {
     Enumerate,
     /// An un-numbered list.
     Itemize,
+    /// A list of terms (each item's label) and their descriptions.
+    Description,
+    /// A list of tasks, each with a box which is ticked when it's done.
+    ///
+    /// # Note
+    ///
+    /// The symbols come from the package named by `CheckboxStyle::package()`,
+    /// so make sure it's in your preamble.
+    Checklist(CheckboxStyle),
 }
 
 impl ListKind {
@@ -29,10 +207,101 @@ impl ListKind {
         match *self {
             ListKind::Enumerate => "enumerate",
             ListKind::Itemize => "itemize",
+            ListKind::Description => "description",
+            ListKind::Checklist(_) => "itemize",
         }
     }
 }
 
+/// The counter styles which can be used in an `enumitem` label.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Numbering {
+    /// 1, 2, 3, ...
+    Arabic,
+    /// a, b, c, ...
+    LowerAlpha,
+    /// A, B, C, ...
+    UpperAlpha,
+    /// i, ii, iii, ...
+    LowerRoman,
+    /// I, II, III, ...
+    UpperRoman,
+}
+
+impl Numbering {
+    /// Create a label format with some text either side of the counter.
+    ///
+    /// ```rust
+    /// use latex::Numbering;
+    ///
+    /// assert_eq!(Numbering::LowerAlpha.label("(", ")"), r"(\alph*)");
+    /// assert_eq!(Numbering::LowerRoman.label("", "."), r"\roman*.");
+    /// ```
+    pub fn label(&self, before: &str, after: &str) -> String {
+        let counter = match *self {
+            Numbering::Arabic => r"\arabic*",
+            Numbering::LowerAlpha => r"\alph*",
+            Numbering::UpperAlpha => r"\Alph*",
+            Numbering::LowerRoman => r"\roman*",
+            Numbering::UpperRoman => r"\Roman*",
+        };
+
+        format!("{}{}{}", before, counter, after)
+    }
+}
+
+/// Options for customising a list's appearance.
+///
+/// # Note
+///
+/// These options are implemented by the `enumitem` package, so you'll need
+/// to add it to your preamble (e.g. with `Preamble::require_package()`) if
+/// any are set.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ListOptions {
+    /// The number to start counting from.
+    pub start: Option<usize>,
+    /// The label format, e.g. `(\alph*)` (see `Numbering::label()`).
+    pub label: Option<String>,
+    /// Remove the vertical space between items.
+    pub compact: bool,
+    /// Continue numbering from where the previous list left off.
+    pub resume: bool,
+}
+
+impl ListOptions {
+    /// Are all options left at their defaults?
+    pub fn is_empty(&self) -> bool {
+        *self == ListOptions::default()
+    }
+}
+
+impl Display for ListOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut options = Vec::new();
+
+        if let Some(start) = self.start {
+            options.push(format!("start={}", start));
+        }
+        if let Some(ref label) = self.label {
+            // a `,` or `]` would otherwise end the value early
+            if label.contains(',') || label.contains(']') {
+                options.push(format!("label={{{}}}", label));
+            } else {
+                options.push(format!("label={}", label));
+            }
+        }
+        if self.compact {
+            options.push(String::from("nosep"));
+        }
+        if self.resume {
+            options.push(String::from("resume"));
+        }
+
+        write!(f, "{}", options.join(", "))
+    }
+}
+
 /// A list (either dot points or numbered).
 ///
 /// # Examples
@@ -57,10 +326,32 @@ impl ListKind {
 /// \item Dot-points
 /// \end{itemize}
 /// ```
+///
+/// Lists can also be customised using `enumitem` options.
+///
+/// ```rust
+/// use latex::{List, ListKind, Numbering};
+///
+/// let mut list = List::new(ListKind::Enumerate);
+/// list.options.label = Some(Numbering::LowerAlpha.label("(", ")"));
+/// list.options.compact = true;
+/// list.push("First").push("Second");
+/// ```
+///
+/// Which would be rendered as:
+///
+/// ```tex
+/// \begin{enumerate}[label=(\alph*), nosep]
+/// \item First
+/// \item Second
+/// \end{enumerate}
+/// ```
 #[derive(Clone, Debug, PartialEq)]
 pub struct List {
     /// The kind of list this is.
     pub kind: ListKind,
+    /// Any `enumitem` options for the list.
+    pub options: ListOptions,
     items: Vec<Item>,
 }
 
@@ -69,20 +360,167 @@ impl List {
     pub fn new(kind: ListKind) -> List {
         List {
             kind,
+            options: ListOptions::default(),
             items: Vec::new(),
         }
     }
 
-    /// Add an element to the list.
-    pub fn push<S: AsRef<str>>(&mut self, item: S) -> &mut Self {
-        self.items.push(Item(item.as_ref().to_string()));
+    /// Add an item to the list.
+    pub fn push<I: Into<Item>>(&mut self, item: I) -> &mut Self {
+        self.items.push(item.into());
         self
     }
 
     /// Iterate over the items in the list.
-    pub fn iter(&self) -> Iter<Item> {
+    pub fn iter(&self) -> Iter<'_, Item> {
         self.items.iter()
     }
+
+    /// Iterate over the items in the list, for rewriting them in place.
+    pub(crate) fn iter_mut(&mut self) -> IterMut<'_, Item> {
+        self.items.iter_mut()
+    }
+
+    /// Parse a Markdown task list (`- [ ] todo` and `- [x] done`) into a
+    /// `Checklist`.
+    ///
+    /// Items indented further than the one before them become a nested
+    /// checklist inside that item.
+    ///
+    /// ```rust
+    /// use latex::List;
+    ///
+    /// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let src = "- [x] Write the code\n- [ ] Write the docs\n  - [ ] Examples\n";
+    /// let list = List::from_markdown(src).map_err(|e| e.compat())?;
+    ///
+    /// assert_eq!(list.to_markdown().map_err(|e| e.compat())?, src);
+    /// # Ok(())
+    /// # }
+    /// # fn main() {
+    /// # run().unwrap();
+    /// # }
+    /// ```
+    pub fn from_markdown(src: &str) -> Result<List, Error> {
+        // each level of nesting along with its indent
+        let mut stack: Vec<(usize, List)> = Vec::new();
+
+        for (i, line) in src.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let indent = line.len() - line.trim_start().len();
+            let (done, text) = parse_task(line.trim_start())
+                .ok_or_else(|| format_err!("Line {} isn't a task list item: {:?}", i + 1, line))?;
+
+            while stack.len() > 1 && indent < stack[stack.len() - 1].0 {
+                close_nested_list(&mut stack);
+            }
+
+            let nested = match stack.last() {
+                Some(&(top, ref list)) => indent > top && !list.items.is_empty(),
+                None => true,
+            };
+            if nested {
+                stack.push((
+                    indent,
+                    List::new(ListKind::Checklist(CheckboxStyle::default())),
+                ));
+            }
+
+            let last = stack.len() - 1;
+            stack[last].1.push(Item::task(done, text));
+        }
+
+        while stack.len() > 1 {
+            close_nested_list(&mut stack);
+        }
+
+        Ok(stack
+            .pop()
+            .map(|(_, list)| list)
+            .unwrap_or_else(|| List::new(ListKind::Checklist(CheckboxStyle::default()))))
+    }
+
+    /// Write the list out as a Markdown task list.
+    ///
+    /// Plain text is written as-is, while any other formatting is written
+    /// using its `tex` representation.
+    pub fn to_markdown(&self) -> Result<String, Error> {
+        let mut buffer = Vec::new();
+        self.write_markdown(&mut buffer, 0)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+
+    fn write_markdown(&self, buffer: &mut Vec<u8>, depth: usize) -> Result<(), Error> {
+        for item in self.iter() {
+            buffer.extend("  ".repeat(depth).bytes());
+            match item.checked {
+                Some(true) => buffer.extend(b"- [x]"),
+                Some(false) => buffer.extend(b"- [ ]"),
+                None => buffer.push(b'-'),
+            }
+
+            let mut text = Vec::new();
+            {
+                let mut printer = Printer::new(&mut text);
+                for elem in item.content.iter() {
+                    printer.visit_paragraph_element(elem)?;
+                }
+            }
+            if !text.is_empty() {
+                buffer.push(b' ');
+                buffer.extend(text);
+            }
+            buffer.push(b'\n');
+
+            for elem in item.iter() {
+                if let Element::List(ref nested) = *elem {
+                    nested.write_markdown(buffer, depth + 1)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Split a Markdown task list item into whether it's done and its text.
+fn parse_task(line: &str) -> Option<(bool, &str)> {
+    let rest = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))?;
+
+    let (done, text) = if let Some(text) = rest.strip_prefix("[ ]") {
+        (false, text)
+    } else if let Some(text) = rest
+        .strip_prefix("[x]")
+        .or_else(|| rest.strip_prefix("[X]"))
+    {
+        (true, text)
+    } else {
+        return None;
+    };
+
+    // an empty task is fine, but the text has to be separated from the box
+    if text.is_empty() {
+        Some((done, text))
+    } else {
+        text.strip_prefix(' ').map(|text| (done, text))
+    }
+}
+
+/// Pop the innermost list off the stack and add it to its parent's last item.
+fn close_nested_list(stack: &mut Vec<(usize, List)>) {
+    if let Some((_, nested)) = stack.pop() {
+        if let Some(&mut (_, ref mut parent)) = stack.last_mut() {
+            if let Some(item) = parent.items.last_mut() {
+                item.push(nested);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -97,4 +535,60 @@ mod tests {
         list.push("Hello World");
         assert_eq!(list.items.len(), 1);
     }
+
+    #[test]
+    fn nest_a_list_inside_an_item() {
+        let mut inner = List::new(ListKind::Enumerate);
+        inner.push("First").push("Second");
+
+        let mut item = Item::from("Outer");
+        item.push(inner.clone());
+
+        assert_eq!(item.iter().collect::<Vec<_>>(), vec![&Element::List(inner)]);
+    }
+
+    #[test]
+    fn parse_markdown_task_list() {
+        let src = "* [ ] Outer\n    + [X] Inner\n- [x] Second\n";
+        let list = List::from_markdown(src).unwrap();
+
+        let mut inner = List::new(ListKind::Checklist(CheckboxStyle::Amssymb));
+        inner.push(Item::task(true, "Inner"));
+        let mut outer = Item::task(false, "Outer");
+        outer.push(inner);
+        let mut should_be = List::new(ListKind::Checklist(CheckboxStyle::Amssymb));
+        should_be.push(outer).push(Item::task(true, "Second"));
+
+        assert_eq!(list, should_be);
+    }
+
+    #[test]
+    fn empty_markdown_tasks() {
+        let src = "- [ ]\n- [x] Done\n";
+        let list = List::from_markdown(src).unwrap();
+
+        assert_eq!(list.iter().count(), 2);
+        assert_eq!(list.to_markdown().unwrap(), src);
+        assert!(List::from_markdown("- [ ]oops\n").is_err());
+    }
+
+    #[test]
+    fn markdown_needs_checkboxes() {
+        assert!(List::from_markdown("- [ ] fine\n- not a task\n").is_err());
+    }
+
+    #[test]
+    fn format_list_options() {
+        let mut options = ListOptions::default();
+        assert!(options.is_empty());
+        assert_eq!(options.to_string(), "");
+
+        options.start = Some(3);
+        options.label = Some(Numbering::UpperRoman.label("", "."));
+        options.resume = true;
+        assert_eq!(options.to_string(), r"start=3, label=\Roman*., resume");
+
+        options.label = Some(Numbering::Arabic.label("", ","));
+        assert_eq!(options.to_string(), r"start=3, label={\arabic*,}, resume");
+    }
 }
@@ -0,0 +1,345 @@
+use std::fmt::{self, Display, Formatter};
+use std::slice::Iter;
+
+use command::Command;
+use equations::{Align, Equation};
+use failure::Error;
+use paragraph::ParagraphElement;
+
+/// The delimiters drawn around a `Matrix`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MatrixDelimiter {
+    /// No delimiters at all (`matrix`).
+    Plain,
+    /// Parentheses (`pmatrix`).
+    Paren,
+    /// Square brackets (`bmatrix`).
+    Bracket,
+    /// Curly braces (`Bmatrix`).
+    Brace,
+    /// Single vertical bars, typically used for determinants (`vmatrix`).
+    Bar,
+    /// Double vertical bars, typically used for norms (`Vmatrix`).
+    DoubleBar,
+}
+
+impl Default for MatrixDelimiter {
+    fn default() -> Self {
+        MatrixDelimiter::Plain
+    }
+}
+
+impl MatrixDelimiter {
+    /// Get the name of the `amsmath` environment for this delimiter.
+    pub fn environment_name(&self) -> &str {
+        match *self {
+            MatrixDelimiter::Plain => "matrix",
+            MatrixDelimiter::Paren => "pmatrix",
+            MatrixDelimiter::Bracket => "bmatrix",
+            MatrixDelimiter::Brace => "Bmatrix",
+            MatrixDelimiter::Bar => "vmatrix",
+            MatrixDelimiter::DoubleBar => "Vmatrix",
+        }
+    }
+}
+
+/// How the numbers in a column should be formatted.
+///
+/// Cells which can't be parsed as a number are left untouched.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NumberFormat {
+    /// Fixed-point notation with the given number of decimal places.
+    Fixed(usize),
+    /// Scientific notation (e.g. `1.50 \times 10^{3}`) with the given number
+    /// of decimal places in the mantissa.
+    Scientific(usize),
+}
+
+impl NumberFormat {
+    /// Format a single cell.
+    pub fn format(&self, cell: &str) -> String {
+        let value: f64 = match cell.trim().parse() {
+            Ok(v) => v,
+            Err(_) => return cell.to_string(),
+        };
+
+        match *self {
+            NumberFormat::Fixed(precision) => format!("{:.*}", precision, value),
+            // there's no exponent to pull out of "inf" or "NaN"
+            NumberFormat::Scientific(_) if !value.is_finite() => cell.to_string(),
+            NumberFormat::Scientific(precision) => {
+                let formatted = format!("{:.*e}", precision, value);
+                let (mantissa, exponent) = formatted.split_at(formatted.find('e').unwrap());
+                format!(r"{} \times 10^{{{}}}", mantissa, &exponent[1..])
+            }
+        }
+    }
+}
+
+/// A matrix of values, rendered using one of the `amsmath` matrix
+/// environments.
+///
+/// # Note
+///
+/// Using this requires you to include the `amsmath` package in your preamble.
+///
+/// # Examples
+///
+/// A `Matrix` can be created from anything which can be iterated over as rows
+/// of `Display`-able values, for example a `Vec<Vec<f64>>`.
+///
+/// ```rust
+/// use latex::{Matrix, MatrixDelimiter, NumberFormat};
+///
+/// let mut m = Matrix::new(MatrixDelimiter::Bracket, vec![vec![1.0, 0.5], vec![0.25, 2.0]]);
+/// m.format_column(1, NumberFormat::Fixed(2)).highlight_row(0);
+/// ```
+///
+/// Which would be rendered as:
+///
+/// ```tex
+/// \begin{bmatrix}
+/// \mathbf{1} & \mathbf{0.50} \\
+/// 0.25 & 2.00
+/// \end{bmatrix}
+/// ```
+///
+/// Because a matrix is just math-mode text it can be embedded in an
+/// `Equation`, an `Align` or inline math using `into()`, or with `format!()`
+/// when it's only part of a larger expression.
+///
+/// ```rust
+/// use latex::{Equation, Matrix, MatrixDelimiter, ParagraphElement};
+///
+/// let m = Matrix::new(MatrixDelimiter::Paren, vec![vec![1, 2], vec![3, 4]]);
+///
+/// let eq = Equation::new(format!("A &= {}", m));
+/// let inline: ParagraphElement = m.into();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Matrix {
+    /// The delimiters drawn around the matrix.
+    pub delimiter: MatrixDelimiter,
+    rows: Vec<Vec<String>>,
+    column_formats: Vec<Option<NumberFormat>>,
+    highlighted_rows: Vec<usize>,
+    highlighted_columns: Vec<usize>,
+    highlight_command: Option<String>,
+}
+
+impl Matrix {
+    /// Create a new matrix from some rows of values.
+    pub fn new<I>(delimiter: MatrixDelimiter, rows: I) -> Matrix
+    where
+        I: IntoIterator,
+        I::Item: IntoIterator,
+        <I::Item as IntoIterator>::Item: Display,
+    {
+        let mut matrix = Matrix {
+            delimiter,
+            ..Default::default()
+        };
+
+        for row in rows {
+            matrix.push_row(row);
+        }
+
+        matrix
+    }
+
+    /// Add a row to the bottom of the matrix.
+    pub fn push_row<I>(&mut self, row: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: Display,
+    {
+        self.rows
+            .push(row.into_iter().map(|cell| cell.to_string()).collect());
+        self
+    }
+
+    /// Set how the numbers in a particular column should be formatted.
+    pub fn format_column(&mut self, column: usize, format: NumberFormat) -> &mut Self {
+        if self.column_formats.len() <= column {
+            self.column_formats.resize(column + 1, None);
+        }
+        self.column_formats[column] = Some(format);
+        self
+    }
+
+    /// Highlight every cell in a row.
+    pub fn highlight_row(&mut self, row: usize) -> &mut Self {
+        self.highlighted_rows.push(row);
+        self
+    }
+
+    /// Highlight every cell in a column.
+    pub fn highlight_column(&mut self, column: usize) -> &mut Self {
+        self.highlighted_columns.push(column);
+        self
+    }
+
+    /// Set the command used to highlight cells (`mathbf` by default).
+    ///
+    /// The name is checked the same way as a `Command`'s, so something like
+    /// `"text bf"` is rejected rather than silently producing broken TeX.
+    pub fn highlight_with(&mut self, command: &str) -> Result<&mut Self, Error> {
+        if !Command::new(command).has_valid_name() {
+            bail!("\\{} isn't a valid command name", command);
+        }
+
+        self.highlight_command = Some(command.to_string());
+        Ok(self)
+    }
+
+    /// Iterate over the (unformatted) rows of the matrix.
+    pub fn iter(&self) -> Iter<'_, Vec<String>> {
+        self.rows.iter()
+    }
+
+    /// Get a cell as it will be rendered, with any formatting and
+    /// highlighting applied.
+    pub fn rendered_cell(&self, row: usize, column: usize) -> Option<String> {
+        let cell = self.rows.get(row)?.get(column)?;
+
+        let formatted = match self.column_formats.get(column) {
+            Some(Some(format)) => format.format(cell),
+            _ => cell.clone(),
+        };
+
+        if self.highlighted_rows.contains(&row) || self.highlighted_columns.contains(&column) {
+            let command = self.highlight_command.as_deref().unwrap_or("mathbf");
+            Some(format!(r"\{}{{{}}}", command, formatted))
+        } else {
+            Some(formatted)
+        }
+    }
+}
+
+impl<T: Display> From<Vec<Vec<T>>> for Matrix {
+    fn from(other: Vec<Vec<T>>) -> Matrix {
+        Matrix::new(MatrixDelimiter::default(), other)
+    }
+}
+
+impl Display for Matrix {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let env = self.delimiter.environment_name();
+        writeln!(f, r"\begin{{{}}}", env)?;
+
+        for (i, row) in self.rows.iter().enumerate() {
+            let cells: Vec<String> = (0..row.len())
+                .filter_map(|j| self.rendered_cell(i, j))
+                .collect();
+            write!(f, "{}", cells.join(" & "))?;
+
+            if i + 1 < self.rows.len() {
+                write!(f, r" \\")?;
+            }
+            writeln!(f)?;
+        }
+
+        write!(f, r"\end{{{}}}", env)
+    }
+}
+
+impl From<Matrix> for Equation {
+    fn from(other: Matrix) -> Equation {
+        Equation::new(other.to_string())
+    }
+}
+
+impl From<Matrix> for Align {
+    /// Wrap the matrix in a single-equation `align`.
+    fn from(other: Matrix) -> Align {
+        let mut align = Align::new();
+        align.push(other);
+        align
+    }
+}
+
+impl From<Matrix> for ParagraphElement {
+    /// Use the matrix as inline math.
+    fn from(other: Matrix) -> ParagraphElement {
+        ParagraphElement::InlineMath(other.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_plain_matrix() {
+        let should_be = "\\begin{matrix}\n1 & 2 \\\\\n3 & 4\n\\end{matrix}";
+        let m = Matrix::from(vec![vec![1, 2], vec![3, 4]]);
+
+        assert_eq!(m.to_string(), should_be);
+    }
+
+    #[test]
+    fn render_matrix_with_delimiters() {
+        let should_be = "\\begin{Vmatrix}\nx\n\\end{Vmatrix}";
+        let m = Matrix::new(MatrixDelimiter::DoubleBar, vec![vec!["x"]]);
+
+        assert_eq!(m.to_string(), should_be);
+    }
+
+    #[test]
+    fn column_formatting_only_touches_numbers() {
+        let mut m = Matrix::new(
+            MatrixDelimiter::Paren,
+            vec![vec!["1.23456", "1500"], vec![r"\pi", "-0.00025"]],
+        );
+        m.format_column(0, NumberFormat::Fixed(2))
+            .format_column(1, NumberFormat::Scientific(1));
+
+        assert_eq!(m.rendered_cell(0, 0).unwrap(), "1.23");
+        assert_eq!(m.rendered_cell(1, 0).unwrap(), r"\pi");
+        assert_eq!(m.rendered_cell(0, 1).unwrap(), r"1.5 \times 10^{3}");
+        assert_eq!(m.rendered_cell(1, 1).unwrap(), r"-2.5 \times 10^{-4}");
+    }
+
+    #[test]
+    fn highlighted_rows_and_columns() {
+        let mut m = Matrix::from(vec![vec![1, 2], vec![3, 4]]);
+        m.highlight_row(0)
+            .highlight_column(1)
+            .highlight_with("boxed")
+            .unwrap();
+
+        assert_eq!(m.rendered_cell(0, 0).unwrap(), r"\boxed{1}");
+        assert_eq!(m.rendered_cell(1, 0).unwrap(), "3");
+        assert_eq!(m.rendered_cell(1, 1).unwrap(), r"\boxed{4}");
+    }
+
+    #[test]
+    fn invalid_highlight_commands() {
+        let mut m = Matrix::from(vec![vec![1]]);
+
+        for name in ["", "text bf", "mathbf{", "#1"] {
+            assert!(m.highlight_with(name).is_err(), "{:?}", name);
+        }
+        assert!(m.highlight_command.is_none());
+    }
+
+    #[test]
+    fn non_finite_values_are_left_alone() {
+        let format = NumberFormat::Scientific(2);
+
+        assert_eq!(format.format("inf"), "inf");
+        assert_eq!(format.format("-inf"), "-inf");
+        assert_eq!(format.format("NaN"), "NaN");
+    }
+
+    #[test]
+    fn matrix_as_inline_math() {
+        let m = Matrix::from(vec![vec![1]]);
+        let elem: ParagraphElement = m.into();
+
+        assert_eq!(
+            elem,
+            ParagraphElement::InlineMath("\\begin{matrix}\n1\n\\end{matrix}".to_string())
+        );
+    }
+}
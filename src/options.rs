@@ -0,0 +1,348 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use document::DocumentClass;
+use failure::Error;
+
+/// The base font sizes supported by the standard document classes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[allow(missing_docs)]
+pub enum PointSize {
+    Ten,
+    Eleven,
+    Twelve,
+}
+
+/// The paper sizes supported by the standard document classes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[allow(missing_docs)]
+pub enum PaperSize {
+    A4,
+    A5,
+    B5,
+    Letter,
+    Legal,
+    Executive,
+}
+
+/// An option passed to `\documentclass`.
+///
+/// # Examples
+///
+/// ```rust
+/// use latex::{ClassOption, Document, DocumentClass, PaperSize, PointSize};
+///
+/// let mut doc = Document::new(DocumentClass::Report);
+/// doc.class_option(ClassOption::PointSize(PointSize::Eleven))
+///     .class_option(ClassOption::Paper(PaperSize::A4))
+///     .class_option(ClassOption::TwoSide)
+///     .class_option(ClassOption::Other(String::from("openright")));
+///
+/// assert_eq!(
+///     latex::print(&doc).unwrap().lines().next(),
+///     Some(r"\documentclass[11pt,a4paper,twoside,openright]{report}")
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum ClassOption {
+    /// The base font size (`10pt`, `11pt` or `12pt`).
+    PointSize(PointSize),
+    /// The paper size (`a4paper`, `letterpaper`, ...).
+    Paper(PaperSize),
+    /// `oneside`
+    OneSide,
+    /// `twoside`
+    TwoSide,
+    /// `onecolumn`
+    OneColumn,
+    /// `twocolumn`
+    TwoColumn,
+    /// `titlepage`
+    TitlePage,
+    /// `notitlepage`
+    NoTitlePage,
+    /// `draft`
+    Draft,
+    /// `final`
+    Final,
+    /// `landscape`
+    Landscape,
+    /// `fleqn`, which aligns display math to the left.
+    Fleqn,
+    /// `leqno`, which puts equation numbers on the left.
+    Leqno,
+    /// `openright`, which starts chapters on a right-hand page.
+    OpenRight,
+    /// `openany`, which starts chapters on the next page.
+    OpenAny,
+    /// Any other option, written as-is.
+    Other(String),
+}
+
+impl ClassOption {
+    /// Options which can't be used together share a group.
+    fn group(&self) -> Option<&'static str> {
+        match *self {
+            ClassOption::PointSize(_) => Some("font size"),
+            ClassOption::Paper(_) => Some("paper size"),
+            ClassOption::OneSide | ClassOption::TwoSide => Some("sides"),
+            ClassOption::OneColumn | ClassOption::TwoColumn => Some("columns"),
+            ClassOption::TitlePage | ClassOption::NoTitlePage => Some("title page"),
+            ClassOption::Draft | ClassOption::Final => Some("draft"),
+            ClassOption::OpenRight | ClassOption::OpenAny => Some("chapter opening"),
+            _ => None,
+        }
+    }
+
+    /// Is this option what a standard document class does anyway (e.g.
+    /// `titlepage` for a `report`)?
+    pub fn is_default_for(&self, class: &DocumentClass) -> bool {
+        let standard = matches!(
+            *class,
+            DocumentClass::Article | DocumentClass::Report | DocumentClass::Book
+        );
+
+        match *self {
+            ClassOption::PointSize(PointSize::Ten)
+            | ClassOption::Paper(PaperSize::Letter)
+            | ClassOption::OneColumn
+            | ClassOption::Final => standard,
+            ClassOption::NoTitlePage => *class == DocumentClass::Article,
+            ClassOption::TitlePage => {
+                *class == DocumentClass::Report || *class == DocumentClass::Book
+            }
+            ClassOption::OneSide => {
+                *class == DocumentClass::Article || *class == DocumentClass::Report
+            }
+            ClassOption::TwoSide | ClassOption::OpenRight => *class == DocumentClass::Book,
+            ClassOption::OpenAny => *class == DocumentClass::Report,
+            _ => false,
+        }
+    }
+
+    /// Does a particular document class understand this option?
+    ///
+    /// Classes other than the standard ones are assumed to support
+    /// everything.
+    pub fn is_supported_by(&self, class: &DocumentClass) -> bool {
+        !matches!(
+            (self, class),
+            (&ClassOption::OpenRight, &DocumentClass::Article)
+                | (&ClassOption::OpenAny, &DocumentClass::Article)
+        )
+    }
+}
+
+impl Display for ClassOption {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let name = match *self {
+            ClassOption::PointSize(PointSize::Ten) => "10pt",
+            ClassOption::PointSize(PointSize::Eleven) => "11pt",
+            ClassOption::PointSize(PointSize::Twelve) => "12pt",
+            ClassOption::Paper(PaperSize::A4) => "a4paper",
+            ClassOption::Paper(PaperSize::A5) => "a5paper",
+            ClassOption::Paper(PaperSize::B5) => "b5paper",
+            ClassOption::Paper(PaperSize::Letter) => "letterpaper",
+            ClassOption::Paper(PaperSize::Legal) => "legalpaper",
+            ClassOption::Paper(PaperSize::Executive) => "executivepaper",
+            ClassOption::OneSide => "oneside",
+            ClassOption::TwoSide => "twoside",
+            ClassOption::OneColumn => "onecolumn",
+            ClassOption::TwoColumn => "twocolumn",
+            ClassOption::TitlePage => "titlepage",
+            ClassOption::NoTitlePage => "notitlepage",
+            ClassOption::Draft => "draft",
+            ClassOption::Final => "final",
+            ClassOption::Landscape => "landscape",
+            ClassOption::Fleqn => "fleqn",
+            ClassOption::Leqno => "leqno",
+            ClassOption::OpenRight => "openright",
+            ClassOption::OpenAny => "openany",
+            ClassOption::Other(ref s) => s,
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for ClassOption {
+    type Err = Error;
+
+    /// Parse an option, falling back to `ClassOption::Other` for anything
+    /// which isn't recognised.
+    fn from_str(s: &str) -> Result<ClassOption, Error> {
+        let option = match s.trim() {
+            "10pt" => ClassOption::PointSize(PointSize::Ten),
+            "11pt" => ClassOption::PointSize(PointSize::Eleven),
+            "12pt" => ClassOption::PointSize(PointSize::Twelve),
+            "a4paper" => ClassOption::Paper(PaperSize::A4),
+            "a5paper" => ClassOption::Paper(PaperSize::A5),
+            "b5paper" => ClassOption::Paper(PaperSize::B5),
+            "letterpaper" => ClassOption::Paper(PaperSize::Letter),
+            "legalpaper" => ClassOption::Paper(PaperSize::Legal),
+            "executivepaper" => ClassOption::Paper(PaperSize::Executive),
+            "oneside" => ClassOption::OneSide,
+            "twoside" => ClassOption::TwoSide,
+            "onecolumn" => ClassOption::OneColumn,
+            "twocolumn" => ClassOption::TwoColumn,
+            "titlepage" => ClassOption::TitlePage,
+            "notitlepage" => ClassOption::NoTitlePage,
+            "draft" => ClassOption::Draft,
+            "final" => ClassOption::Final,
+            "landscape" => ClassOption::Landscape,
+            "fleqn" => ClassOption::Fleqn,
+            "leqno" => ClassOption::Leqno,
+            "openright" => ClassOption::OpenRight,
+            "openany" => ClassOption::OpenAny,
+            other => ClassOption::Other(other.to_string()),
+        };
+
+        Ok(option)
+    }
+}
+
+/// Check a list of options makes sense for a document class, removing any
+/// duplicates.
+///
+/// Classes other than the standard ones may define their own options, so
+/// nothing is checked for `DocumentClass::Other`.
+pub(crate) fn resolve_options<I>(
+    class: &DocumentClass,
+    options: I,
+) -> Result<Vec<ClassOption>, Error>
+where
+    I: IntoIterator<Item = ClassOption>,
+{
+    let mut resolved: Vec<ClassOption> = Vec::new();
+
+    // known options may have been passed in as a raw, comma-separated string
+    let options = options.into_iter().flat_map(|option| match option {
+        ClassOption::Other(raw) => raw
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::parse)
+            .collect(),
+        known => vec![Ok(known)],
+    });
+
+    for option in options {
+        let option: ClassOption = option?;
+
+        if resolved.contains(&option) {
+            continue;
+        }
+        if let DocumentClass::Other(_) = *class {
+            resolved.push(option);
+            continue;
+        }
+        if !option.is_supported_by(class) {
+            bail!(
+                "The {} class doesn't support the \"{}\" option",
+                class,
+                option
+            );
+        }
+        if let Some(group) = option.group() {
+            if let Some(existing) = resolved.iter().find(|o| o.group() == Some(group)) {
+                bail!(
+                    "The \"{}\" and \"{}\" options both set the {}",
+                    existing,
+                    option,
+                    group
+                );
+            }
+        }
+
+        resolved.push(option);
+    }
+
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options_round_trip_through_strings() {
+        let inputs = vec!["12pt", "a4paper", "twocolumn", "leqno", "openany", "custom"];
+
+        for src in inputs {
+            let option: ClassOption = src.parse().unwrap();
+            assert_eq!(option.to_string(), src);
+        }
+    }
+
+    #[test]
+    fn duplicates_are_removed() {
+        let options = vec![ClassOption::Draft, ClassOption::Fleqn, ClassOption::Draft];
+
+        let got = resolve_options(&DocumentClass::Article, options).unwrap();
+
+        assert_eq!(got, vec![ClassOption::Draft, ClassOption::Fleqn]);
+    }
+
+    #[test]
+    fn conflicting_options_are_an_error() {
+        let options = vec![
+            ClassOption::Paper(PaperSize::A4),
+            ClassOption::Paper(PaperSize::Letter),
+        ];
+
+        assert!(resolve_options(&DocumentClass::Article, options).is_err());
+    }
+
+    #[test]
+    fn articles_dont_have_chapters_to_open() {
+        assert!(resolve_options(&DocumentClass::Article, vec![ClassOption::OpenRight]).is_err());
+        assert!(resolve_options(&DocumentClass::Book, vec![ClassOption::OpenRight]).is_ok());
+    }
+
+    #[test]
+    fn raw_options_are_checked_too() {
+        let options = vec![
+            ClassOption::Paper(PaperSize::A4),
+            ClassOption::Other(String::from("a5paper")),
+        ];
+
+        assert!(resolve_options(&DocumentClass::Report, options).is_err());
+    }
+
+    #[test]
+    fn raw_options_are_split_on_commas() {
+        let options = vec![
+            ClassOption::Other(String::from("a4paper, twoside,,draft")),
+            ClassOption::TwoSide,
+        ];
+
+        assert_eq!(
+            resolve_options(&DocumentClass::Report, options).unwrap(),
+            vec![
+                ClassOption::Paper(PaperSize::A4),
+                ClassOption::TwoSide,
+                ClassOption::Draft,
+            ]
+        );
+
+        let options = vec![ClassOption::Other(String::from("openright,openany"))];
+        assert!(resolve_options(&DocumentClass::Book, options).is_err());
+    }
+
+    #[test]
+    fn class_defaults() {
+        assert!(ClassOption::TitlePage.is_default_for(&DocumentClass::Report));
+        assert!(!ClassOption::TitlePage.is_default_for(&DocumentClass::Article));
+        assert!(ClassOption::OpenRight.is_default_for(&DocumentClass::Book));
+        assert!(!ClassOption::OpenRight.is_default_for(&DocumentClass::Report));
+        assert!(!ClassOption::Final.is_default_for(&DocumentClass::Other(String::from("memoir"))));
+    }
+
+    #[test]
+    fn other_classes_are_not_checked() {
+        let class = DocumentClass::Other(String::from("memoir"));
+        let options = vec![ClassOption::Draft, ClassOption::Final];
+
+        assert_eq!(resolve_options(&class, options.clone()).unwrap(), options);
+    }
+}
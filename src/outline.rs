@@ -0,0 +1,253 @@
+use std::iter::FromIterator;
+
+use document::Element;
+use paragraph::ParagraphElement;
+use section::{push_to_section, section_at_level, BOTTOM_LEVEL, TOP_LEVEL};
+
+/// A single event in a flat stream of headings and content, as you'd get
+/// from Markdown or most other external sources.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OutlineEvent {
+    /// A heading at some level, from -1 for a part to 5 for a subparagraph.
+    Heading(i8, ParagraphElement),
+    /// Some content which belongs under the most recent heading.
+    Content(Element),
+}
+
+/// A problem noticed while building an outline.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OutlineWarning {
+    /// A heading which skipped one or more levels (e.g. a subsubsection
+    /// directly inside a section).
+    SkippedLevel {
+        /// The index of the heading in the event stream.
+        index: usize,
+        /// The level of the heading it was nested inside.
+        parent: i8,
+        /// The heading's own level.
+        level: i8,
+    },
+    /// A heading level which doesn't exist in LaTeX, so it was clamped to
+    /// the nearest one which does.
+    InvalidLevel {
+        /// The index of the heading in the event stream.
+        index: usize,
+        /// The level which was asked for.
+        level: i8,
+    },
+    /// Content which came before the first heading, so it was left at the
+    /// top level.
+    OrphanContent {
+        /// The index of the content in the event stream.
+        index: usize,
+    },
+}
+
+/// The result of building an outline.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Outline {
+    /// The top-level elements, with every heading's content nested inside it.
+    pub elements: Vec<Element>,
+    /// Anything unusual noticed along the way.
+    pub warnings: Vec<OutlineWarning>,
+}
+
+/// Assemble a flat stream of headings and content into nested sectioning
+/// elements.
+///
+/// # Examples
+///
+/// ```rust
+/// use latex::{Chapter, Element, OutlineBuilder, Section};
+///
+/// let mut builder = OutlineBuilder::new();
+/// builder
+///     .heading(0, "Introduction")
+///     .content("Some text.")
+///     .heading(1, "Background")
+///     .content("More text.")
+///     .heading(0, "Method");
+///
+/// let outline = builder.finish();
+///
+/// let mut background = Section::new("Background");
+/// background.push("More text.");
+/// let mut intro = Chapter::new("Introduction");
+/// intro.push("Some text.").push(background);
+///
+/// assert_eq!(
+///     outline.elements,
+///     vec![Element::from(intro), Element::from(Chapter::new("Method"))]
+/// );
+/// assert!(outline.warnings.is_empty());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OutlineBuilder {
+    /// The headings which are still open, innermost last.
+    open: Vec<(i8, Element)>,
+    outline: Outline,
+    events: usize,
+}
+
+impl OutlineBuilder {
+    /// Create an empty `OutlineBuilder`.
+    pub fn new() -> OutlineBuilder {
+        Default::default()
+    }
+
+    /// Add a single event to the outline.
+    pub fn push(&mut self, event: OutlineEvent) -> &mut Self {
+        match event {
+            OutlineEvent::Heading(level, title) => self.heading(level, title),
+            OutlineEvent::Content(element) => self.content(element),
+        }
+    }
+
+    /// Start a new heading, closing any open headings at the same or a
+    /// deeper level.
+    pub fn heading<P: Into<ParagraphElement>>(&mut self, level: i8, title: P) -> &mut Self {
+        let index = self.next_index();
+
+        let clamped = level.clamp(TOP_LEVEL, BOTTOM_LEVEL);
+        if clamped != level {
+            self.outline
+                .warnings
+                .push(OutlineWarning::InvalidLevel { index, level });
+        }
+
+        while matches!(self.open.last(), Some(&(open, _)) if open >= clamped) {
+            self.close_innermost();
+        }
+
+        if let Some(&(parent, _)) = self.open.last() {
+            if clamped > parent + 1 {
+                self.outline.warnings.push(OutlineWarning::SkippedLevel {
+                    index,
+                    parent,
+                    level: clamped,
+                });
+            }
+        }
+
+        self.open
+            .push((clamped, section_at_level(clamped, title.into())));
+        self
+    }
+
+    /// Add some content to the most recent heading.
+    pub fn content<E: Into<Element>>(&mut self, element: E) -> &mut Self {
+        let index = self.next_index();
+
+        match self.open.last_mut() {
+            Some(&mut (_, ref mut section)) => {
+                push_to_section(section, element.into());
+            }
+            None => {
+                self.outline
+                    .warnings
+                    .push(OutlineWarning::OrphanContent { index });
+                self.outline.elements.push(element.into());
+            }
+        }
+
+        self
+    }
+
+    /// Close every open heading and return the finished outline.
+    pub fn finish(mut self) -> Outline {
+        while !self.open.is_empty() {
+            self.close_innermost();
+        }
+
+        self.outline
+    }
+
+    fn next_index(&mut self) -> usize {
+        self.events += 1;
+        self.events - 1
+    }
+
+    fn close_innermost(&mut self) {
+        if let Some((_, section)) = self.open.pop() {
+            match self.open.last_mut() {
+                Some(&mut (_, ref mut parent)) => {
+                    push_to_section(parent, section);
+                }
+                None => self.outline.elements.push(section),
+            }
+        }
+    }
+}
+
+impl Extend<OutlineEvent> for OutlineBuilder {
+    fn extend<T: IntoIterator<Item = OutlineEvent>>(&mut self, iter: T) {
+        for event in iter {
+            self.push(event);
+        }
+    }
+}
+
+impl FromIterator<OutlineEvent> for Outline {
+    fn from_iter<T: IntoIterator<Item = OutlineEvent>>(iter: T) -> Outline {
+        let mut builder = OutlineBuilder::new();
+        builder.extend(iter);
+        builder.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use section::{Section, Subsection, Subsubsection};
+
+    #[test]
+    fn report_orphans_and_skipped_levels() {
+        let events = vec![
+            OutlineEvent::Content(Element::from("Preface")),
+            OutlineEvent::Heading(1, ParagraphElement::from("Results")),
+            OutlineEvent::Heading(3, ParagraphElement::from("Table 1")),
+            OutlineEvent::Content(Element::from("Data")),
+            OutlineEvent::Heading(2, ParagraphElement::from("Discussion")),
+        ];
+
+        let outline: Outline = events.into_iter().collect();
+
+        let mut table = Subsubsection::new("Table 1");
+        table.push("Data");
+        let mut results = Section::new("Results");
+        results.push(table).push(Subsection::new("Discussion"));
+
+        assert_eq!(
+            outline.elements,
+            vec![Element::from("Preface"), Element::from(results)]
+        );
+        assert_eq!(
+            outline.warnings,
+            vec![
+                OutlineWarning::OrphanContent { index: 0 },
+                OutlineWarning::SkippedLevel {
+                    index: 2,
+                    parent: 1,
+                    level: 3
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn clamp_invalid_levels() {
+        let mut builder = OutlineBuilder::new();
+        builder.heading(9, "Too deep");
+
+        let outline = builder.finish();
+
+        assert_eq!(
+            outline.warnings,
+            vec![OutlineWarning::InvalidLevel { index: 0, level: 9 }]
+        );
+        assert_eq!(
+            outline.elements,
+            vec![Element::from(::section::Subparagraph::new("Too deep"))]
+        );
+    }
+}
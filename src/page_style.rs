@@ -0,0 +1,199 @@
+use document::PreambleElement;
+use length::Length;
+use paragraph::ParagraphElement;
+
+/// What goes on the left, in the centre and on the right of a header or
+/// footer.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HeaderFooter {
+    /// The left-hand side.
+    pub left: Option<ParagraphElement>,
+    /// The middle.
+    pub center: Option<ParagraphElement>,
+    /// The right-hand side.
+    pub right: Option<ParagraphElement>,
+}
+
+impl HeaderFooter {
+    /// Create an empty header or footer.
+    pub fn new() -> HeaderFooter {
+        Default::default()
+    }
+
+    /// Set the left-hand side.
+    pub fn left<P: Into<ParagraphElement>>(&mut self, content: P) -> &mut Self {
+        self.left = Some(content.into());
+        self
+    }
+
+    /// Set the middle.
+    pub fn center<P: Into<ParagraphElement>>(&mut self, content: P) -> &mut Self {
+        self.center = Some(content.into());
+        self
+    }
+
+    /// Set the right-hand side.
+    pub fn right<P: Into<ParagraphElement>>(&mut self, content: P) -> &mut Self {
+        self.right = Some(content.into());
+        self
+    }
+
+    /// Is there nothing in the header or footer?
+    pub fn is_empty(&self) -> bool {
+        self.left.is_none() && self.center.is_none() && self.right.is_none()
+    }
+
+    /// Each part which has been set, with the `fancyhdr` letter for its
+    /// position.
+    pub fn parts(&self) -> Vec<(char, &ParagraphElement)> {
+        let parts = [('L', &self.left), ('C', &self.center), ('R', &self.right)];
+
+        parts
+            .iter()
+            .filter_map(|&(position, part)| part.as_ref().map(|p| (position, p)))
+            .collect()
+    }
+}
+
+/// A page style, defined in the preamble with `fancyhdr`'s
+/// `\fancypagestyle`.
+///
+/// The style starts with an empty header and footer, so only the parts you
+/// set are shown. If an even header or footer is set, the normal ones are
+/// only used on odd pages (this needs a `twoside` document).
+///
+/// `\maketitle` and `\chapter` switch the page they are on to the `plain`
+/// style. Setting a first page header or footer redefines `plain` as well,
+/// so those pages get their own look.
+///
+/// The rule under the header (or above the footer) is only drawn next to a
+/// header (or footer) which has something in it, on first pages as well as
+/// the rest.
+///
+/// Once a style is defined, use it with `Element::PageStyle` or
+/// `Element::ThisPageStyle`.
+///
+/// # Examples
+///
+/// ```rust
+/// use latex::{Command, Document, DocumentClass, Element, PageStyle, ParagraphElement};
+///
+/// let mut page_of = Command::new("pageref");
+/// page_of.argument("LastPage");
+///
+/// let mut style = PageStyle::new("report");
+/// style.header.left("ACME Corp").right("Confidential");
+/// style.footer.center(ParagraphElement::span(vec![
+///     ParagraphElement::from("Page "),
+///     Command::new("thepage").into(),
+///     ParagraphElement::from(" of "),
+///     page_of.into(),
+/// ]));
+///
+/// let mut doc = Document::new(DocumentClass::Article);
+/// doc.preamble.use_package("lastpage").page_style(style);
+/// doc.push(Element::PageStyle(String::from("report")));
+/// ```
+///
+/// Which renders the preamble as:
+///
+/// ```tex
+/// \usepackage{lastpage}
+/// \usepackage{fancyhdr}
+/// \fancypagestyle{report}{
+/// \fancyhf{}
+/// \fancyhead[L]{ACME Corp}
+/// \fancyhead[R]{Confidential}
+/// \fancyfoot[C]{Page \thepage{} of \pageref{LastPage}}
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PageStyle {
+    /// The style's name.
+    pub name: String,
+    /// The header, on every page or just odd ones.
+    pub header: HeaderFooter,
+    /// The footer, on every page or just odd ones.
+    pub footer: HeaderFooter,
+    /// A different header for even pages.
+    pub even_header: Option<HeaderFooter>,
+    /// A different footer for even pages.
+    pub even_footer: Option<HeaderFooter>,
+    /// The header on first pages (the `plain` style).
+    pub first_header: Option<HeaderFooter>,
+    /// The footer on first pages (the `plain` style).
+    pub first_footer: Option<HeaderFooter>,
+    /// The thickness of the line under the header (`fancyhdr` uses `0.4pt`
+    /// by default).
+    pub head_rule: Option<Length>,
+    /// The thickness of the line above the footer (`fancyhdr` uses `0pt` by
+    /// default).
+    pub foot_rule: Option<Length>,
+}
+
+impl PageStyle {
+    /// Create a page style with an empty header and footer.
+    pub fn new(name: &str) -> PageStyle {
+        PageStyle {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Use a different header on even pages.
+    pub fn even_header(&mut self, header: HeaderFooter) -> &mut Self {
+        self.even_header = Some(header);
+        self
+    }
+
+    /// Use a different footer on even pages.
+    pub fn even_footer(&mut self, footer: HeaderFooter) -> &mut Self {
+        self.even_footer = Some(footer);
+        self
+    }
+
+    /// Use a different header on first pages.
+    pub fn first_header(&mut self, header: HeaderFooter) -> &mut Self {
+        self.first_header = Some(header);
+        self
+    }
+
+    /// Use a different footer on first pages.
+    pub fn first_footer(&mut self, footer: HeaderFooter) -> &mut Self {
+        self.first_footer = Some(footer);
+        self
+    }
+
+    /// Set the thickness of the line under the header.
+    pub fn head_rule(&mut self, width: Length) -> &mut Self {
+        self.head_rule = Some(width);
+        self
+    }
+
+    /// Set the thickness of the line above the footer.
+    pub fn foot_rule(&mut self, width: Length) -> &mut Self {
+        self.foot_rule = Some(width);
+        self
+    }
+
+    /// Does the style treat odd and even pages differently?
+    pub fn has_even_pages(&self) -> bool {
+        self.even_header.is_some() || self.even_footer.is_some()
+    }
+
+    /// Does the style also define the `plain` style used on first pages?
+    pub fn has_first_pages(&self) -> bool {
+        self.first_header.is_some() || self.first_footer.is_some()
+    }
+}
+
+impl From<PageStyle> for PreambleElement {
+    fn from(other: PageStyle) -> Self {
+        PreambleElement::PageStyle(Box::new(other))
+    }
+}
+
+/// Is this one of the page styles LaTeX always has?
+pub(crate) fn is_builtin_page_style(name: &str) -> bool {
+    matches!(name, "empty" | "plain" | "headings" | "myheadings")
+}
@@ -1,5 +1,9 @@
 use std::slice::Iter;
 
+use color::Color;
+use command::Command;
+use length::Length;
+
 /// A single paragraph.
 ///
 /// # Examples
@@ -52,7 +56,7 @@ impl Paragraph {
     }
 
     /// Iterate over the `ParagraphElement`s in this `Paragraph`.
-    pub fn iter(&self) -> Iter<ParagraphElement> {
+    pub fn iter(&self) -> Iter<'_, ParagraphElement> {
         self.elements.iter()
     }
 }
@@ -65,10 +69,19 @@ impl<'a> From<&'a str> for Paragraph {
     }
 }
 
+impl From<ParagraphElement> for Paragraph {
+    fn from(other: ParagraphElement) -> Paragraph {
+        let mut para = Paragraph::new();
+        para.push(other);
+        para
+    }
+}
+
 /// The various paragraph elements.
 ///
 /// For convenience, you can convert from a string to a `ParagraphElement`
-/// using `into()`.
+/// using `into()`. Formatting one with `Display` gives the same `tex` that the
+/// `Printer` would emit.
 #[derive(Clone, Debug, PartialEq)]
 pub enum ParagraphElement {
     /// A plain string.
@@ -81,6 +94,116 @@ pub enum ParagraphElement {
     Link((String, String)),
     /// An inline mathematical expression.
     InlineMath(String),
+    /// A snippet of source code, rendered using the `Document`'s
+    /// `CodeBackend`.
+    InlineCode(String),
+    /// Emphasised text (`\emph`).
+    Emph(Box<ParagraphElement>),
+    /// Underlined text.
+    Underline(Box<ParagraphElement>),
+    /// Monospaced text (`\texttt`).
+    Monospace(Box<ParagraphElement>),
+    /// Text in small capitals (`\textsc`).
+    SmallCaps(Box<ParagraphElement>),
+    /// Struck-through text (`\sout`).
+    ///
+    /// # Note
+    ///
+    /// This requires the `ulem` package, which you'll probably want to import
+    /// with the `normalem` option so `\emph` keeps using italics.
+    StrikeThrough(Box<ParagraphElement>),
+    /// Superscript text (`\textsuperscript`).
+    Superscript(Box<ParagraphElement>),
+    /// Subscript text (`\textsubscript`).
+    Subscript(Box<ParagraphElement>),
+    /// Text in a different font size, e.g. `{\small ...}`.
+    FontSize(FontSize, Box<ParagraphElement>),
+    /// Several elements grouped together, so formatting can be applied to
+    /// mixed content.
+    Span(Vec<ParagraphElement>),
+    /// A footnote.
+    Footnote(Box<ParagraphElement>),
+    /// A footnote mark without its text (`\footnotemark`), for places where
+    /// a footnote isn't allowed (e.g. tables). The text comes later with a
+    /// `FootnoteText`.
+    FootnoteMark,
+    /// The text for the most recent `FootnoteMark`.
+    FootnoteText(Box<ParagraphElement>),
+    /// A note in the margin (`\marginpar`).
+    MarginNote(Box<ParagraphElement>),
+    /// A `todonotes` comment.
+    ///
+    /// # Note
+    ///
+    /// These are only rendered when the `Document` is in review mode, in
+    /// which case you'll need the `todonotes` package.
+    Todo(Box<ParagraphElement>),
+    /// A call to an arbitrary macro.
+    Command(Command),
+    /// Horizontal space (`\hspace`).
+    HSpace(Length),
+    /// Stretchable horizontal space, e.g. to push the rest of the line to
+    /// the right (`\hfill`).
+    HFill,
+    /// End the line here without starting a new paragraph (`\linebreak`).
+    LineBreak,
+    /// A space which won't be broken across lines (`~`).
+    NonBreakingSpace,
+    /// Coloured text (`\textcolor`).
+    Colored(Color, Box<ParagraphElement>),
+    /// Text on a coloured background (`\colorbox`).
+    ColorBox(Color, Box<ParagraphElement>),
+    /// Text on a coloured background with a coloured frame (`\fcolorbox`),
+    /// as (frame, background, text).
+    ///
+    /// # Note
+    ///
+    /// `\fcolorbox` only takes one colour model, so the two colours must
+    /// either both be named (or mixes) or both use the same model.
+    FramedColorBox(Color, Color, Box<ParagraphElement>),
+}
+
+/// The standard LaTeX font size commands.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FontSize {
+    /// `\tiny`
+    Tiny,
+    /// `\scriptsize`
+    ScriptSize,
+    /// `\footnotesize`
+    FootnoteSize,
+    /// `\small`
+    Small,
+    /// `\normalsize`
+    NormalSize,
+    /// `\large`
+    Large,
+    /// `\Large`
+    XLarge,
+    /// `\LARGE`
+    XXLarge,
+    /// `\huge`
+    Huge,
+    /// `\Huge`
+    XHuge,
+}
+
+impl FontSize {
+    /// Get the name of the command which switches to this size.
+    pub fn command_name(&self) -> &str {
+        match *self {
+            FontSize::Tiny => "tiny",
+            FontSize::ScriptSize => "scriptsize",
+            FontSize::FootnoteSize => "footnotesize",
+            FontSize::Small => "small",
+            FontSize::NormalSize => "normalsize",
+            FontSize::Large => "large",
+            FontSize::XLarge => "Large",
+            FontSize::XXLarge => "LARGE",
+            FontSize::Huge => "huge",
+            FontSize::XHuge => "Huge",
+        }
+    }
 }
 
 impl ParagraphElement {
@@ -99,6 +222,156 @@ impl ParagraphElement {
     {
         ParagraphElement::Bold(Box::new(elem.into()))
     }
+
+    /// Convenience method for emphasising a `ParagraphElement`.
+    pub fn emph<E>(elem: E) -> ParagraphElement
+    where
+        E: Into<ParagraphElement>,
+    {
+        ParagraphElement::Emph(Box::new(elem.into()))
+    }
+
+    /// Convenience method for underlining a `ParagraphElement`.
+    pub fn underline<E>(elem: E) -> ParagraphElement
+    where
+        E: Into<ParagraphElement>,
+    {
+        ParagraphElement::Underline(Box::new(elem.into()))
+    }
+
+    /// Convenience method for writing a `ParagraphElement` in a monospaced
+    /// font.
+    pub fn monospace<E>(elem: E) -> ParagraphElement
+    where
+        E: Into<ParagraphElement>,
+    {
+        ParagraphElement::Monospace(Box::new(elem.into()))
+    }
+
+    /// Convenience method for writing a `ParagraphElement` in small capitals.
+    pub fn small_caps<E>(elem: E) -> ParagraphElement
+    where
+        E: Into<ParagraphElement>,
+    {
+        ParagraphElement::SmallCaps(Box::new(elem.into()))
+    }
+
+    /// Convenience method for striking through a `ParagraphElement`.
+    pub fn strike_through<E>(elem: E) -> ParagraphElement
+    where
+        E: Into<ParagraphElement>,
+    {
+        ParagraphElement::StrikeThrough(Box::new(elem.into()))
+    }
+
+    /// Convenience method for raising a `ParagraphElement` into a superscript.
+    pub fn superscript<E>(elem: E) -> ParagraphElement
+    where
+        E: Into<ParagraphElement>,
+    {
+        ParagraphElement::Superscript(Box::new(elem.into()))
+    }
+
+    /// Convenience method for lowering a `ParagraphElement` into a subscript.
+    pub fn subscript<E>(elem: E) -> ParagraphElement
+    where
+        E: Into<ParagraphElement>,
+    {
+        ParagraphElement::Subscript(Box::new(elem.into()))
+    }
+
+    /// Convenience method for changing the font size of a `ParagraphElement`.
+    pub fn sized<E>(size: FontSize, elem: E) -> ParagraphElement
+    where
+        E: Into<ParagraphElement>,
+    {
+        ParagraphElement::FontSize(size, Box::new(elem.into()))
+    }
+
+    /// Convenience method for creating a footnote.
+    ///
+    /// ```rust
+    /// use latex::{Paragraph, ParagraphElement};
+    ///
+    /// let mut p = Paragraph::new();
+    /// p.push("LaTeX")
+    ///  .push(ParagraphElement::footnote(ParagraphElement::span(vec![
+    ///      ParagraphElement::from("Pronounced "),
+    ///      ParagraphElement::italic("lay-tech"),
+    ///  ])))
+    ///  .push(" is great.");
+    /// ```
+    pub fn footnote<E>(elem: E) -> ParagraphElement
+    where
+        E: Into<ParagraphElement>,
+    {
+        ParagraphElement::Footnote(Box::new(elem.into()))
+    }
+
+    /// Convenience method for creating a margin note.
+    pub fn margin_note<E>(elem: E) -> ParagraphElement
+    where
+        E: Into<ParagraphElement>,
+    {
+        ParagraphElement::MarginNote(Box::new(elem.into()))
+    }
+
+    /// Convenience method for creating a todo note.
+    pub fn todo<E>(elem: E) -> ParagraphElement
+    where
+        E: Into<ParagraphElement>,
+    {
+        ParagraphElement::Todo(Box::new(elem.into()))
+    }
+
+    /// Convenience method for creating coloured text.
+    pub fn colored<C, E>(color: C, elem: E) -> ParagraphElement
+    where
+        C: Into<Color>,
+        E: Into<ParagraphElement>,
+    {
+        ParagraphElement::Colored(color.into(), Box::new(elem.into()))
+    }
+
+    /// Convenience method for putting text on a coloured background.
+    pub fn color_box<C, E>(background: C, elem: E) -> ParagraphElement
+    where
+        C: Into<Color>,
+        E: Into<ParagraphElement>,
+    {
+        ParagraphElement::ColorBox(background.into(), Box::new(elem.into()))
+    }
+
+    /// Convenience method for putting text on a coloured background with a
+    /// coloured frame.
+    pub fn framed_color_box<C, D, E>(frame: C, background: D, elem: E) -> ParagraphElement
+    where
+        C: Into<Color>,
+        D: Into<Color>,
+        E: Into<ParagraphElement>,
+    {
+        ParagraphElement::FramedColorBox(frame.into(), background.into(), Box::new(elem.into()))
+    }
+
+    /// Convenience method for grouping several elements together.
+    ///
+    /// ```rust
+    /// use latex::ParagraphElement;
+    ///
+    /// let elem = ParagraphElement::bold(ParagraphElement::span(vec![
+    ///     ParagraphElement::from("Very "),
+    ///     ParagraphElement::italic("important"),
+    /// ]));
+    ///
+    /// assert_eq!(elem.to_string(), r"\textbf{Very \textit{important}}");
+    /// ```
+    pub fn span<I>(elems: I) -> ParagraphElement
+    where
+        I: IntoIterator,
+        I::Item: Into<ParagraphElement>,
+    {
+        ParagraphElement::Span(elems.into_iter().map(Into::into).collect())
+    }
 }
 
 impl Default for ParagraphElement {
@@ -113,18 +386,8 @@ impl<'a> From<&'a str> for ParagraphElement {
     }
 }
 
-impl std::fmt::Display for ParagraphElement {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match *self {
-            ParagraphElement::Plain(ref s) => write!(f, "{}", s)?,
-            ParagraphElement::Link(ref s) => write!(f, "\\href{{{}}}{{{}}}", s.0, s.1)?,
-            ParagraphElement::InlineMath(ref s) => write!(f, "${}$", s)?,
-            ParagraphElement::Bold(ref e) => write!(f, r"\textbf{{{}}}", e)?,
-            ParagraphElement::Italic(ref e) => {
-                write!(f, r"\textit{{{}}}", e)?;
-            }
-        }
-
-        Ok(())
+impl From<Vec<ParagraphElement>> for ParagraphElement {
+    fn from(other: Vec<ParagraphElement>) -> Self {
+        ParagraphElement::Span(other)
     }
 }
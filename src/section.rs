@@ -1,5 +1,7 @@
 use document::Element;
+use failure::Error;
 use paragraph::ParagraphElement;
+use std::mem;
 use std::slice::Iter;
 
 /// A trait to represent all types of Section
@@ -7,17 +9,25 @@ pub trait SectionElement {
     /// Get if element is numbered
     fn numbered(&self) -> bool;
     /// Iterate throw elements
-    fn iter(&self) -> Iter<Element>;
+    fn iter(&self) -> Iter<'_, Element>;
     /// Check if empty
     fn is_empty(&self) -> bool;
     /// Get name of element
     fn get_name(&self) -> &ParagraphElement;
     /// Get name of section
     fn get_section_name(&self) -> &str;
+    /// Get the sectioning level, from -1 for a part to 5 for a subparagraph
+    fn level(&self) -> i8;
+    /// Get the short name used in the table of contents and headers
+    fn get_short_name(&self) -> Option<&ParagraphElement>;
+    /// Get the label attached to the section
+    fn get_label(&self) -> Option<&str>;
+    /// Check if an unnumbered section should still be in the table of contents
+    fn in_toc(&self) -> bool;
 }
 
 macro_rules! create_section_type {
-    ($section_name:ident, $section_tex:literal) => {
+    ($section_name:ident, $section_tex:literal, $level:literal) => {
         /// A document SectionElement.
         ///
         /// Like the `Document` type, a `$section_tex` is more or less just a collection of
@@ -33,6 +43,12 @@ macro_rules! create_section_type {
             sectioning_name: String,
             /// Numbered section
             pub numbered: bool,
+            /// A shorter name for the table of contents and page headers
+            pub short_name: Option<ParagraphElement>,
+            /// A label for referencing the section
+            pub label: Option<String>,
+            /// Add an unnumbered section to the table of contents anyway
+            pub in_toc: bool,
         }
 
         impl $section_name {
@@ -40,22 +56,34 @@ macro_rules! create_section_type {
             pub fn new(name: &str) -> Self {
                 Self {
                     name: ParagraphElement::from(name),
-                    elements: Default::default(),
                     sectioning_name: $section_tex.to_owned(),
                     numbered: true,
+                    ..Default::default()
                 }
             }
 
             /// Create a new section with the specified and formatted name.
             pub fn new_formatted(name: ParagraphElement) -> Self {
                 Self {
-                    name: name,
-                    elements: Default::default(),
+                    name,
                     sectioning_name: $section_tex.to_owned(),
                     numbered: true,
+                    ..Default::default()
                 }
             }
 
+            /// Set the shorter name used in the table of contents.
+            pub fn short_name<P: Into<ParagraphElement>>(&mut self, name: P) -> &mut Self {
+                self.short_name = Some(name.into());
+                self
+            }
+
+            /// Give the section a label.
+            pub fn label(&mut self, name: &str) -> &mut Self {
+                self.label = Some(name.to_string());
+                self
+            }
+
             /// Add an element to the Section.
             pub fn push<I>(&mut self, element: I) -> &mut Self
             where
@@ -79,8 +107,24 @@ macro_rules! create_section_type {
                 &self.sectioning_name
             }
 
+            fn level(&self) -> i8 {
+                $level
+            }
+
+            fn get_short_name(&self) -> Option<&ParagraphElement> {
+                self.short_name.as_ref()
+            }
+
+            fn get_label(&self) -> Option<&str> {
+                self.label.as_deref()
+            }
+
+            fn in_toc(&self) -> bool {
+                self.in_toc
+            }
+
             /// Iterate over the elements in this list.
-            fn iter(&self) -> Iter<Element> {
+            fn iter(&self) -> Iter<'_, Element> {
                 self.elements.iter()
             }
 
@@ -89,13 +133,327 @@ macro_rules! create_section_type {
                 self.elements.is_empty()
             }
         }
+
+        impl $section_name {
+            fn into_parts(self) -> SectionParts {
+                SectionParts {
+                    name: self.name,
+                    elements: self.elements,
+                    numbered: self.numbered,
+                    short_name: self.short_name,
+                    label: self.label,
+                    in_toc: self.in_toc,
+                }
+            }
+
+            fn from_parts(parts: SectionParts) -> Self {
+                Self {
+                    name: parts.name,
+                    elements: parts.elements,
+                    sectioning_name: $section_tex.to_owned(),
+                    numbered: parts.numbered,
+                    short_name: parts.short_name,
+                    label: parts.label,
+                    in_toc: parts.in_toc,
+                }
+            }
+        }
+
+        impl From<$section_name> for Element {
+            fn from(other: $section_name) -> Self {
+                Element::$section_name(other)
+            }
+        }
+    };
+}
+
+create_section_type!(Part, "part", -1);
+create_section_type!(Chapter, "chapter", 0);
+create_section_type!(Section, "section", 1);
+create_section_type!(Subsection, "subsection", 2);
+create_section_type!(Subsubsection, "subsubsection", 3);
+// named so it doesn't clash with a normal `Paragraph`
+create_section_type!(ParagraphSection, "paragraph", 4);
+create_section_type!(Subparagraph, "subparagraph", 5);
+
+/// The contents of a sectioning element, independent of its level.
+struct SectionParts {
+    name: ParagraphElement,
+    elements: Vec<Element>,
+    numbered: bool,
+    short_name: Option<ParagraphElement>,
+    label: Option<String>,
+    in_toc: bool,
+}
+
+/// The highest sectioning level (`\part`).
+pub(crate) const TOP_LEVEL: i8 = -1;
+/// The lowest sectioning level (`\subparagraph`).
+pub(crate) const BOTTOM_LEVEL: i8 = 5;
+
+/// What to do when shifting a heading would take it above `\part` or below
+/// `\subparagraph`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LevelOverflow {
+    /// Fail with an error.
+    Error,
+    /// Clamp the heading to the nearest level which exists.
+    Flatten,
+}
+
+/// Promote or demote a sectioning element (and every sectioning element
+/// nested inside it) by some number of levels.
+///
+/// A positive `by` demotes headings (e.g. `Section` to `Subsection`) while a
+/// negative one promotes them. Headings nested inside environments,
+/// theorems, proofs and list items are shifted too, and anything else is
+/// returned unchanged.
+///
+/// # Examples
+///
+/// ```rust
+/// use latex::{shift_headings, Element, LevelOverflow, Section, Subsection};
+///
+/// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut section = Section::new("Results");
+/// section.push(Subsection::new("Raw Data"));
+///
+/// let shifted = shift_headings(section.into(), 1, LevelOverflow::Error)
+///     .map_err(|e| e.compat())?;
+///
+/// let mut should_be = Subsection::new("Results");
+/// should_be.push(latex::Subsubsection::new("Raw Data"));
+/// assert_eq!(shifted, Element::from(should_be));
+/// # Ok(())
+/// # }
+/// # fn main() {
+/// # run().unwrap();
+/// # }
+/// ```
+pub fn shift_headings(element: Element, by: i8, overflow: LevelOverflow) -> Result<Element, Error> {
+    let level = match section_level(&element) {
+        Some(level) => level,
+        None => return shift_nested_headings(element, by, overflow),
     };
+    let mut parts = into_section_parts(element);
+
+    let new_level = match level.checked_add(by) {
+        Some(new_level) if (TOP_LEVEL..=BOTTOM_LEVEL).contains(&new_level) => new_level,
+        _ if overflow == LevelOverflow::Error => bail!(
+            "Shifting the level {} heading {:?} by {} would put it outside the levels LaTeX supports",
+            level,
+            parts.name,
+            by
+        ),
+        _ if by > 0 => BOTTOM_LEVEL,
+        _ => TOP_LEVEL,
+    };
+
+    shift_all(&mut parts.elements, by, overflow)?;
+
+    Ok(from_section_parts(new_level, parts))
 }
 
-create_section_type!(Part, "part");
-create_section_type!(Chapter, "chapter");
-create_section_type!(Section, "section");
-create_section_type!(Subsection, "subsection");
-create_section_type!(Subsubsection, "subsubsection");
-// create_section_type!(Paragraph, "paragraph");
-// create_section_type!(Subparagraph, "subparagraph");
+/// Shift any headings inside an element which isn't a sectioning element
+/// itself, but can still contain one.
+fn shift_nested_headings(
+    mut element: Element,
+    by: i8,
+    overflow: LevelOverflow,
+) -> Result<Element, Error> {
+    match element {
+        Element::Block(ref mut env) => shift_all(env.elements_mut(), by, overflow)?,
+        Element::Theorem(ref mut theorem) => shift_all(theorem.elements_mut(), by, overflow)?,
+        Element::Proof(ref mut proof) => shift_all(proof.elements_mut(), by, overflow)?,
+        Element::List(ref mut list) => {
+            for item in list.iter_mut() {
+                shift_all(item.elements_mut(), by, overflow)?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(element)
+}
+
+fn shift_all(elements: &mut Vec<Element>, by: i8, overflow: LevelOverflow) -> Result<(), Error> {
+    *elements = mem::take(elements)
+        .into_iter()
+        .map(|child| shift_headings(child, by, overflow))
+        .collect::<Result<_, _>>()?;
+    Ok(())
+}
+
+/// Create an empty sectioning element at a particular level.
+pub(crate) fn section_at_level(level: i8, name: ParagraphElement) -> Element {
+    let parts = SectionParts {
+        name,
+        elements: Vec::new(),
+        numbered: true,
+        short_name: None,
+        label: None,
+        in_toc: false,
+    };
+
+    from_section_parts(level, parts)
+}
+
+/// Add a child element to a sectioning element using its `push()` method.
+pub(crate) fn push_to_section(parent: &mut Element, child: Element) {
+    match *parent {
+        Element::Part(ref mut s) => {
+            s.push(child);
+        }
+        Element::Chapter(ref mut s) => {
+            s.push(child);
+        }
+        Element::Section(ref mut s) => {
+            s.push(child);
+        }
+        Element::Subsection(ref mut s) => {
+            s.push(child);
+        }
+        Element::Subsubsection(ref mut s) => {
+            s.push(child);
+        }
+        Element::ParagraphSection(ref mut s) => {
+            s.push(child);
+        }
+        Element::Subparagraph(ref mut s) => {
+            s.push(child);
+        }
+        ref other => unreachable!("{:?} isn't a sectioning element", other),
+    }
+}
+
+/// Get the level of an element, if it's a sectioning element.
+fn section_level(element: &Element) -> Option<i8> {
+    match *element {
+        Element::Part(ref s) => Some(s.level()),
+        Element::Chapter(ref s) => Some(s.level()),
+        Element::Section(ref s) => Some(s.level()),
+        Element::Subsection(ref s) => Some(s.level()),
+        Element::Subsubsection(ref s) => Some(s.level()),
+        Element::ParagraphSection(ref s) => Some(s.level()),
+        Element::Subparagraph(ref s) => Some(s.level()),
+        _ => None,
+    }
+}
+
+fn into_section_parts(element: Element) -> SectionParts {
+    match element {
+        Element::Part(s) => s.into_parts(),
+        Element::Chapter(s) => s.into_parts(),
+        Element::Section(s) => s.into_parts(),
+        Element::Subsection(s) => s.into_parts(),
+        Element::Subsubsection(s) => s.into_parts(),
+        Element::ParagraphSection(s) => s.into_parts(),
+        Element::Subparagraph(s) => s.into_parts(),
+        other => unreachable!("{:?} isn't a sectioning element", other),
+    }
+}
+
+fn from_section_parts(level: i8, parts: SectionParts) -> Element {
+    match level {
+        -1 => Part::from_parts(parts).into(),
+        0 => Chapter::from_parts(parts).into(),
+        1 => Section::from_parts(parts).into(),
+        2 => Subsection::from_parts(parts).into(),
+        3 => Subsubsection::from_parts(parts).into(),
+        4 => ParagraphSection::from_parts(parts).into(),
+        5 => Subparagraph::from_parts(parts).into(),
+        _ => unreachable!("There is no sectioning level {}", level),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use document::{Document, DocumentClass};
+    use environment::Environment;
+    use lists::{Item, List, ListKind};
+
+    #[test]
+    fn shifting_keeps_section_details() {
+        let mut section = Section::new("Results");
+        section
+            .short_name("Res")
+            .label("sec:results")
+            .push("Some text");
+
+        let shifted = shift_headings(section.into(), -1, LevelOverflow::Error).unwrap();
+
+        let mut should_be = Chapter::new("Results");
+        should_be
+            .short_name("Res")
+            .label("sec:results")
+            .push("Some text");
+        assert_eq!(shifted, Element::from(should_be));
+    }
+
+    #[test]
+    fn shifting_past_subparagraph() {
+        let mut paragraph = ParagraphSection::new("Deep");
+        paragraph.push(Subparagraph::new("Deeper"));
+        let element = Element::from(paragraph);
+
+        assert!(shift_headings(element.clone(), 1, LevelOverflow::Error).is_err());
+
+        let mut should_be = Subparagraph::new("Deep");
+        should_be.push(Subparagraph::new("Deeper"));
+        assert_eq!(
+            shift_headings(element, 1, LevelOverflow::Flatten).unwrap(),
+            Element::from(should_be)
+        );
+    }
+
+    #[test]
+    fn shifting_by_a_huge_amount() {
+        let element = Element::from(Section::new("Far"));
+
+        assert!(shift_headings(element.clone(), i8::MAX, LevelOverflow::Error).is_err());
+        assert_eq!(
+            shift_headings(element.clone(), i8::MAX, LevelOverflow::Flatten).unwrap(),
+            Element::from(Subparagraph::new("Far"))
+        );
+        assert_eq!(
+            shift_headings(element, i8::MIN, LevelOverflow::Flatten).unwrap(),
+            Element::from(Part::new("Far"))
+        );
+    }
+
+    #[test]
+    fn shifting_headings_inside_other_elements() {
+        let mut item = Item::new("Appendix");
+        item.push(Section::new("In a list"));
+        let mut list = List::new(ListKind::Itemize);
+        list.push(item);
+        let mut env = Environment::new("center");
+        env.push(Section::new("In an environment")).push(list);
+
+        let shifted = shift_headings(env.into(), 1, LevelOverflow::Error).unwrap();
+
+        let mut item = Item::new("Appendix");
+        item.push(Subsection::new("In a list"));
+        let mut list = List::new(ListKind::Itemize);
+        list.push(item);
+        let mut should_be = Environment::new("center");
+        should_be
+            .push(Subsection::new("In an environment"))
+            .push(list);
+        assert_eq!(shifted, Element::from(should_be));
+    }
+
+    #[test]
+    fn embed_a_report_in_a_book() {
+        let mut report = Document::new(DocumentClass::Article);
+        report.push("Abstract").push(Section::new("Method"));
+
+        let mut book = Document::new(DocumentClass::Book);
+        book.push_doc_shifted(&report, 1, LevelOverflow::Error)
+            .unwrap();
+
+        assert_eq!(book[0], Element::from("Abstract"));
+        assert_eq!(book[1], Element::from(Subsection::new("Method")));
+    }
+}
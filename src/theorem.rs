@@ -0,0 +1,267 @@
+use std::slice::Iter;
+
+use document::{Element, PreambleElement};
+use paragraph::ParagraphElement;
+
+/// The `amsthm` styles a theorem-like environment can be typeset in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TheoremStyle {
+    /// Bold title and italic body, for theorems, lemmas and corollaries.
+    Plain,
+    /// Bold title and upright body, for definitions and examples.
+    Definition,
+    /// Italic title and upright body, for remarks and notes.
+    Remark,
+}
+
+impl Default for TheoremStyle {
+    fn default() -> Self {
+        TheoremStyle::Plain
+    }
+}
+
+impl TheoremStyle {
+    /// The name passed to `\theoremstyle`.
+    pub fn name(&self) -> &str {
+        match *self {
+            TheoremStyle::Plain => "plain",
+            TheoremStyle::Definition => "definition",
+            TheoremStyle::Remark => "remark",
+        }
+    }
+}
+
+/// Declares a new theorem-like environment with `\newtheorem`.
+///
+/// # Examples
+///
+/// ```rust
+/// use latex::{Document, TheoremDefinition, TheoremStyle};
+///
+/// let mut theorem = TheoremDefinition::new("theorem", "Theorem");
+/// theorem.number_within("section");
+///
+/// let mut lemma = TheoremDefinition::new("lemma", "Lemma");
+/// lemma.shared_with("theorem");
+///
+/// let mut remark = TheoremDefinition::new("remark", "Remark");
+/// remark.style(TheoremStyle::Remark).unnumbered();
+///
+/// let mut doc = Document::default();
+/// doc.preamble
+///     .new_theorem(theorem)
+///     .new_theorem(lemma)
+///     .new_theorem(remark);
+/// ```
+///
+/// Which adds the following to the preamble:
+///
+/// ```tex
+/// \usepackage{amsthm}
+/// \newtheorem{theorem}{Theorem}[section]
+/// \newtheorem{lemma}[theorem]{Lemma}
+/// \theoremstyle{remark}
+/// \newtheorem*{remark}{Remark}
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct TheoremDefinition {
+    /// The name of the environment (e.g. `lemma`).
+    pub name: String,
+    /// The title printed at the start of each block (e.g. `Lemma`).
+    pub title: String,
+    /// How the environment is typeset.
+    pub style: TheoremStyle,
+    /// Is the environment numbered?
+    pub numbered: bool,
+    /// Share a counter with another theorem-like environment, so lemmas and
+    /// theorems get numbered in a single sequence.
+    pub shared_with: Option<String>,
+    /// Reset the counter with each sectioning element of this kind (e.g.
+    /// `section`), numbering blocks like "Theorem 2.1".
+    pub number_within: Option<String>,
+}
+
+impl TheoremDefinition {
+    /// Create a new numbered theorem-like environment with the `plain`
+    /// style.
+    pub fn new(name: &str, title: &str) -> TheoremDefinition {
+        TheoremDefinition {
+            name: name.to_string(),
+            title: title.to_string(),
+            style: TheoremStyle::default(),
+            numbered: true,
+            shared_with: None,
+            number_within: None,
+        }
+    }
+
+    /// Set the environment's style.
+    pub fn style(&mut self, style: TheoremStyle) -> &mut Self {
+        self.style = style;
+        self
+    }
+
+    /// Don't number the environment (`\newtheorem*`).
+    pub fn unnumbered(&mut self) -> &mut Self {
+        self.numbered = false;
+        self
+    }
+
+    /// Share a counter with another theorem-like environment.
+    pub fn shared_with(&mut self, counter: &str) -> &mut Self {
+        self.shared_with = Some(counter.to_string());
+        self
+    }
+
+    /// Reset the counter whenever the given counter (usually a sectioning
+    /// level like `section`) is incremented.
+    pub fn number_within(&mut self, counter: &str) -> &mut Self {
+        self.number_within = Some(counter.to_string());
+        self
+    }
+}
+
+impl From<TheoremDefinition> for PreambleElement {
+    fn from(other: TheoremDefinition) -> Self {
+        PreambleElement::NewTheorem(other)
+    }
+}
+
+/// A block using a theorem-like environment (theorem, lemma, definition,
+/// ...) declared with `Preamble::new_theorem()`.
+///
+/// # Examples
+///
+/// ```rust
+/// use latex::{Align, Theorem};
+///
+/// let mut theorem = Theorem::new("theorem");
+/// theorem
+///     .title("Pythagoras")
+///     .label("thm:pythagoras")
+///     .push("For a right-angled triangle,")
+///     .push(Align::from("a^2 + b^2 &= c^2"));
+/// ```
+///
+/// This renders as:
+///
+/// ```tex
+/// \begin{theorem}[Pythagoras]\label{thm:pythagoras}
+/// For a right-angled triangle,
+///
+/// \begin{align}
+/// a^2 + b^2 &= c^2
+/// \end{align}
+/// \end{theorem}
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Theorem {
+    /// The name of the environment.
+    pub environment: String,
+    /// An optional title, e.g. the theorem's name or where it came from.
+    pub title: Option<ParagraphElement>,
+    /// A label for referencing the block.
+    pub label: Option<String>,
+    elements: Vec<Element>,
+}
+
+impl Theorem {
+    /// Create an empty block using the given environment.
+    pub fn new(environment: &str) -> Theorem {
+        Theorem {
+            environment: environment.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Give the block a title.
+    pub fn title<P: Into<ParagraphElement>>(&mut self, title: P) -> &mut Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Give the block a label.
+    pub fn label(&mut self, name: &str) -> &mut Self {
+        self.label = Some(name.to_string());
+        self
+    }
+
+    /// Add an element to the block's body.
+    pub fn push<E: Into<Element>>(&mut self, element: E) -> &mut Self {
+        self.elements.push(element.into());
+        self
+    }
+
+    /// Iterate over the elements in the block's body.
+    pub fn iter(&self) -> Iter<'_, Element> {
+        self.elements.iter()
+    }
+
+    /// The elements in the block's body, for rewriting them in place.
+    pub(crate) fn elements_mut(&mut self) -> &mut Vec<Element> {
+        &mut self.elements
+    }
+}
+
+impl From<Theorem> for Element {
+    fn from(other: Theorem) -> Self {
+        Element::Theorem(other)
+    }
+}
+
+/// A `proof` environment.
+///
+/// `amsthm` puts the QED symbol at the end of the proof, which ends up on a
+/// line of its own when the proof finishes with display math. When that
+/// happens the `Printer` adds a `\qedhere` to the last line of the math
+/// instead.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Proof {
+    /// Replaces the default "Proof" title, e.g. "Proof of Theorem 1".
+    pub title: Option<ParagraphElement>,
+    elements: Vec<Element>,
+}
+
+impl Proof {
+    /// Create an empty proof.
+    pub fn new() -> Proof {
+        Default::default()
+    }
+
+    /// Give the proof a different title.
+    pub fn title<P: Into<ParagraphElement>>(&mut self, title: P) -> &mut Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Add an element to the proof.
+    pub fn push<E: Into<Element>>(&mut self, element: E) -> &mut Self {
+        self.elements.push(element.into());
+        self
+    }
+
+    /// Iterate over the elements in the proof.
+    pub fn iter(&self) -> Iter<'_, Element> {
+        self.elements.iter()
+    }
+
+    /// The elements in the proof, for rewriting them in place.
+    pub(crate) fn elements_mut(&mut self) -> &mut Vec<Element> {
+        &mut self.elements
+    }
+
+    /// Does the proof end with display math, and therefore need a
+    /// `\qedhere`?
+    pub fn ends_with_math(&self) -> bool {
+        matches!(
+            self.elements.last(),
+            Some(&Element::Align(_)) | Some(&Element::DisplayMath(_))
+        )
+    }
+}
+
+impl From<Proof> for Element {
+    fn from(other: Proof) -> Self {
+        Element::Proof(other)
+    }
+}
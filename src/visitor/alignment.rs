@@ -0,0 +1,319 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+
+use super::Visitor;
+use document::Document;
+use equations::{Align, DisplayMath, Equation, MathEnvironment};
+use failure::Error;
+
+/// Check every `Align` and `DisplayMath` block in a document for inconsistent
+/// alignment points.
+pub fn check_alignment(doc: &Document) -> Result<Vec<AlignmentIssue>, Error> {
+    let mut checker = AlignmentChecker::new();
+    checker.visit_document(doc)?;
+    Ok(checker.into_issues())
+}
+
+/// Count the alignment points (`&`) in a piece of math.
+///
+/// Escaped ampersands (`\&`) and anything inside braces or a nested
+/// environment (e.g. a `pmatrix` or `cases`) are ignored because they belong
+/// to an inner group rather than the surrounding environment.
+pub fn alignment_points(text: &str) -> usize {
+    let mut count = 0;
+    let mut braces = 0_usize;
+    let mut environments = 0_usize;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let mut command = String::new();
+                while let Some(&next) = chars.peek() {
+                    if !next.is_ascii_alphabetic() {
+                        break;
+                    }
+                    command.push(next);
+                    chars.next();
+                }
+
+                match command.as_str() {
+                    // a control symbol like \& or \{
+                    "" => {
+                        chars.next();
+                    }
+                    "begin" => environments += 1,
+                    "end" => environments = environments.saturating_sub(1),
+                    _ => {}
+                }
+            }
+            '{' => braces += 1,
+            '}' => braces = braces.saturating_sub(1),
+            '&' if braces == 0 && environments == 0 => count += 1,
+            _ => {}
+        }
+    }
+
+    count
+}
+
+/// What is wrong with an equation's alignment points.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AlignmentProblem {
+    /// The equation has a different number of alignment points to most of
+    /// the other equations in its block.
+    Inconsistent {
+        /// The number of alignment points used by the rest of the block.
+        expected: usize,
+    },
+    /// The environment doesn't allow alignment points (e.g. `gather`).
+    NotAllowed,
+    /// There are more alignment points than an `alignat{n}` has columns for.
+    TooMany {
+        /// The most alignment points the environment allows.
+        max: usize,
+    },
+}
+
+/// A single equation with badly placed alignment points.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlignmentIssue {
+    /// The index of the offending block, counting every `Align` and
+    /// `DisplayMath` in the order they were visited.
+    pub block: usize,
+    /// The index of the equation within its block.
+    pub index: usize,
+    /// The equation's label, if it has one.
+    pub label: Option<String>,
+    /// How many alignment points the equation has.
+    pub found: usize,
+    /// What is wrong with it.
+    pub problem: AlignmentProblem,
+}
+
+impl Display for AlignmentIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Equation {} of block {}", self.index, self.block)?;
+        if let Some(ref label) = self.label {
+            write!(f, " ({})", label)?;
+        }
+        write!(f, " has {} alignment points", self.found)?;
+
+        match self.problem {
+            AlignmentProblem::Inconsistent { expected } => {
+                write!(f, ", but the rest of the block has {}", expected)
+            }
+            AlignmentProblem::NotAllowed => {
+                write!(f, ", but its environment doesn't allow any")
+            }
+            AlignmentProblem::TooMany { max } => write!(f, ", but at most {} are allowed", max),
+        }
+    }
+}
+
+/// A `Visitor` which tokenizes each equation in a document and checks its
+/// alignment points are consistent and correct for the block's environment.
+///
+/// # Examples
+///
+/// ```rust
+/// use latex::{Align, AlignmentChecker, Equation, Visitor};
+///
+/// let mut align = Align::new();
+/// align.push("y &= mx + c")
+///      .push(Equation::with_label("eq:bad", "a &= b & c &= d"));
+///
+/// let mut checker = AlignmentChecker::new();
+/// checker.visit_align(&align).unwrap();
+///
+/// let issues = checker.into_issues();
+/// assert_eq!(issues.len(), 1);
+/// assert_eq!(issues[0].label, Some(String::from("eq:bad")));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AlignmentChecker {
+    blocks: usize,
+    issues: Vec<AlignmentIssue>,
+}
+
+impl AlignmentChecker {
+    /// Create a new `AlignmentChecker`.
+    pub fn new() -> AlignmentChecker {
+        Default::default()
+    }
+
+    /// The issues found so far.
+    pub fn issues(&self) -> &[AlignmentIssue] {
+        &self.issues
+    }
+
+    /// Consume the checker, returning all the issues it found.
+    pub fn into_issues(self) -> Vec<AlignmentIssue> {
+        self.issues
+    }
+
+    fn check_block(&mut self, environment: &MathEnvironment, equations: &[&Equation]) {
+        let block = self.blocks;
+        self.blocks += 1;
+
+        let counts: Vec<usize> = equations
+            .iter()
+            .map(|eq| alignment_points(eq.get_text()))
+            .collect();
+        let expected = most_common(&counts);
+
+        for (index, (equation, &found)) in equations.iter().zip(&counts).enumerate() {
+            let problem = match *environment {
+                MathEnvironment::Equation | MathEnvironment::Gather | MathEnvironment::Multline
+                    if found > 0 =>
+                {
+                    Some(AlignmentProblem::NotAllowed)
+                }
+                MathEnvironment::Alignat(columns) if found + 1 > 2 * columns => {
+                    Some(AlignmentProblem::TooMany {
+                        max: (2 * columns).saturating_sub(1),
+                    })
+                }
+                MathEnvironment::Align | MathEnvironment::Alignat(_) if found != expected => {
+                    Some(AlignmentProblem::Inconsistent { expected })
+                }
+                _ => None,
+            };
+
+            if let Some(problem) = problem {
+                self.issues.push(AlignmentIssue {
+                    block,
+                    index,
+                    label: equation.get_label().map(String::from),
+                    found,
+                    problem,
+                });
+            }
+        }
+    }
+}
+
+/// Find the most common value, preferring whichever appears first when
+/// there's a tie.
+fn most_common(counts: &[usize]) -> usize {
+    let mut frequencies = HashMap::new();
+    for &count in counts {
+        *frequencies.entry(count).or_insert(0) += 1;
+    }
+
+    let mut best = counts.first().cloned().unwrap_or(0);
+    for &count in counts {
+        if frequencies[&count] > frequencies[&best] {
+            best = count;
+        }
+    }
+
+    best
+}
+
+impl Visitor for AlignmentChecker {
+    fn visit_align(&mut self, align: &Align) -> Result<(), Error> {
+        let equations: Vec<&Equation> = align.iter().collect();
+        self.check_block(&MathEnvironment::Align, &equations);
+        Ok(())
+    }
+
+    fn visit_display_math(&mut self, math: &DisplayMath) -> Result<(), Error> {
+        let equations: Vec<&Equation> = math.iter().collect();
+        self.check_block(&math.environment, &equations);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use {Matrix, MatrixDelimiter, Section};
+
+    #[test]
+    fn count_alignment_points() {
+        let inputs = vec![
+            ("y = mx + c", 0),
+            ("y &= mx + c", 1),
+            (r"a &= b & c &= d", 3),
+            (r"x \& y &= z", 1),
+            (r"f(x) &= \frac{a & b}{c}", 1),
+            (r"&= \begin{cases} 1 & x > 0 \\ 0 & x \leq 0 \end{cases}", 1),
+        ];
+
+        for (src, should_be) in inputs {
+            assert_eq!(alignment_points(src), should_be, "{}", src);
+        }
+    }
+
+    #[test]
+    fn matrices_dont_count() {
+        let m = Matrix::new(MatrixDelimiter::Paren, vec![vec![1, 2], vec![3, 4]]);
+        let eq = format!("A &= {}", m);
+
+        assert_eq!(alignment_points(&eq), 1);
+    }
+
+    #[test]
+    fn gather_doesnt_allow_alignment() {
+        let mut math = DisplayMath::new(MathEnvironment::Gather);
+        math.push("a = b").push("c &= d");
+
+        let mut checker = AlignmentChecker::new();
+        checker.visit_display_math(&math).unwrap();
+
+        assert_eq!(
+            checker.issues(),
+            &[AlignmentIssue {
+                block: 0,
+                index: 1,
+                label: None,
+                found: 1,
+                problem: AlignmentProblem::NotAllowed,
+            }]
+        );
+    }
+
+    #[test]
+    fn alignat_column_limit() {
+        let mut math = DisplayMath::new(MathEnvironment::Alignat(1));
+        math.push("a &= b & c &= d");
+
+        let mut checker = AlignmentChecker::new();
+        checker.visit_display_math(&math).unwrap();
+
+        assert_eq!(
+            checker.issues()[0].problem,
+            AlignmentProblem::TooMany { max: 1 }
+        );
+    }
+
+    #[test]
+    fn check_every_block_in_a_document() {
+        let mut first = Align::new();
+        first.push("a &= b").push("c &= d");
+        let mut second = Align::new();
+        second
+            .push("a &= b")
+            .push(Equation::with_label("eq:odd", "c &= d & e"))
+            .push("f &= g");
+
+        let mut section = Section::new("Equations");
+        section.push(first).push(second);
+        let mut doc = Document::default();
+        doc.push(section);
+
+        let issues = check_alignment(&doc).unwrap();
+
+        assert_eq!(
+            issues,
+            vec![AlignmentIssue {
+                block: 1,
+                index: 1,
+                label: Some(String::from("eq:odd")),
+                found: 2,
+                problem: AlignmentProblem::Inconsistent { expected: 1 },
+            }]
+        );
+    }
+}
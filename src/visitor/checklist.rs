@@ -0,0 +1,111 @@
+use std::fmt::{self, Display, Formatter};
+
+use super::Visitor;
+use document::Document;
+use failure::Error;
+use lists::{List, ListKind};
+
+/// Count how many checklist items in a document are done.
+pub fn summarise_checklists(doc: &Document) -> Result<ChecklistSummary, Error> {
+    let mut summary = ChecklistSummary::default();
+    summary.visit_document(doc)?;
+    Ok(summary)
+}
+
+/// A `Visitor` which tallies up every item drawn with a checkbox, including
+/// nested ones and tasks in ordinary lists.
+///
+/// # Examples
+///
+/// ```rust
+/// use latex::{ChecklistSummary, CheckboxStyle, Item, List, ListKind, Visitor};
+///
+/// let mut list = List::new(ListKind::Checklist(CheckboxStyle::Amssymb));
+/// list.push(Item::task(true, "Write code"))
+///     .push(Item::task(false, "Write docs"));
+///
+/// let mut summary = ChecklistSummary::default();
+/// summary.visit_list(&list).unwrap();
+///
+/// assert_eq!(summary.to_string(), "1 of 2 complete");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ChecklistSummary {
+    /// The number of items which are done.
+    pub done: usize,
+    /// The total number of items.
+    pub total: usize,
+}
+
+impl ChecklistSummary {
+    /// Is every item done?
+    pub fn is_complete(&self) -> bool {
+        self.done == self.total
+    }
+}
+
+impl Display for ChecklistSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} of {} complete", self.done, self.total)
+    }
+}
+
+impl Visitor for ChecklistSummary {
+    fn visit_list(&mut self, list: &List) -> Result<(), Error> {
+        let in_checklist = matches!(list.kind, ListKind::Checklist(_));
+        for item in list.iter() {
+            if item.has_checkbox(in_checklist) {
+                self.total += 1;
+                if item.checked == Some(true) {
+                    self.done += 1;
+                }
+            }
+
+            self.visit_list_item(item)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use {CheckboxStyle, Item, Section};
+
+    #[test]
+    fn count_nested_checklists_in_a_document() {
+        let mut nested = List::new(ListKind::Checklist(CheckboxStyle::Amssymb));
+        nested
+            .push(Item::task(true, "Inner 1"))
+            .push(Item::task(true, "Inner 2"));
+        let mut outer = Item::task(false, "Outer");
+        outer.push(nested);
+
+        let mut checklist = List::new(ListKind::Checklist(CheckboxStyle::Amssymb));
+        checklist.push(outer);
+        let mut plain = List::new(ListKind::Itemize);
+        plain.push("Not a task");
+
+        let mut section = Section::new("Release");
+        section.push(checklist).push(plain);
+        let mut doc = Document::default();
+        doc.push(section);
+
+        let summary = summarise_checklists(&doc).unwrap();
+
+        assert_eq!(summary, ChecklistSummary { done: 2, total: 3 });
+        assert!(!summary.is_complete());
+    }
+
+    #[test]
+    fn count_tasks_in_ordinary_lists() {
+        let mut list = List::new(ListKind::Itemize);
+        list.push("Not a task").push(Item::task(true, "Done"));
+
+        let mut summary = ChecklistSummary::default();
+        summary.visit_list(&list).unwrap();
+
+        assert_eq!(summary, ChecklistSummary { done: 1, total: 1 });
+    }
+}
@@ -0,0 +1,166 @@
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::str;
+
+use super::{Printer, Visitor};
+use command::Command;
+use document::{Document, Element};
+use environment::Environment;
+use equations::{Align, DisplayMath, Equation};
+use lists::{Item, List};
+use paragraph::{Paragraph, ParagraphElement};
+use section::{Chapter, ParagraphSection, Part, Section, Subparagraph, Subsection, Subsubsection};
+use theorem::{Proof, Theorem};
+
+/// An adapter which lets a `Printer` write to anything implementing
+/// `fmt::Write` (e.g. a `String` or a `Formatter`).
+///
+/// # Examples
+///
+/// ```rust
+/// use latex::{FmtWriter, Paragraph, Printer, Visitor};
+///
+/// let mut rendered = String::new();
+/// {
+///     let mut printer = Printer::new(FmtWriter::new(&mut rendered));
+///     printer.visit_paragraph(&Paragraph::from("Hello World")).unwrap();
+/// }
+///
+/// assert_eq!(rendered, "Hello World\n");
+/// ```
+#[derive(Debug)]
+pub struct FmtWriter<W> {
+    inner: W,
+}
+
+impl<W> FmtWriter<W>
+where
+    W: fmt::Write,
+{
+    /// Wrap something implementing `fmt::Write`.
+    pub fn new(inner: W) -> FmtWriter<W> {
+        FmtWriter { inner }
+    }
+
+    /// Get back the wrapped writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W> io::Write for FmtWriter<W>
+where
+    W: fmt::Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let s = str::from_utf8(buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        self.inner
+            .write_str(s)
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Implement `Display` by running the `Printer` over a node, writing
+/// straight into the `Formatter`, so `format!("{}", node)` gives exactly what
+/// `print()` would emit.
+///
+/// `Display` can't report why a node couldn't be printed, so if the printer
+/// fails (e.g. because a `Command` has an invalid name) this returns
+/// `fmt::Error`, which makes `to_string()` panic. Use `print()` or a
+/// `Printer` directly if you need to handle the error.
+macro_rules! display_via_printer {
+    ($($node:ty => $visit:ident;)*) => {
+        $(
+            impl Display for $node {
+                fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                    Printer::new(FmtWriter::new(&mut *f))
+                        .$visit(self)
+                        .map_err(|_| fmt::Error)
+                }
+            }
+        )*
+    };
+}
+
+display_via_printer! {
+    Document => visit_document;
+    Element => visit_element;
+    Paragraph => visit_paragraph;
+    ParagraphElement => visit_paragraph_element;
+    List => visit_list;
+    Item => visit_list_item;
+    Align => visit_align;
+    DisplayMath => visit_display_math;
+    Equation => visit_equation;
+    Part => visit_sectioning_element;
+    Chapter => visit_sectioning_element;
+    Section => visit_sectioning_element;
+    Subsection => visit_sectioning_element;
+    Subsubsection => visit_sectioning_element;
+    ParagraphSection => visit_sectioning_element;
+    Subparagraph => visit_sectioning_element;
+    Command => visit_command;
+    Environment => visit_environment;
+    Theorem => visit_theorem;
+    Proof => visit_proof;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use visitor::print;
+    use {DocumentClass, ListKind};
+
+    #[test]
+    fn document_display_matches_print() {
+        let mut list = List::new(ListKind::Itemize);
+        list.push("One").push("Two");
+
+        let mut section = Section::new("Section");
+        section
+            .push("Some text")
+            .push(list)
+            .push(Align::from("y &= mx + c"));
+
+        let mut doc = Document::new(DocumentClass::Article);
+        doc.preamble.title("Title").use_package("amsmath");
+        doc.push(Element::TitlePage).push(section);
+
+        assert_eq!(doc.to_string(), print(&doc).unwrap());
+    }
+
+    #[test]
+    fn paragraph_element_display_matches_printer() {
+        let elem = ParagraphElement::bold(vec![
+            ParagraphElement::from("Hello "),
+            ParagraphElement::italic("World"),
+        ]);
+
+        let mut buffer = Vec::new();
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_paragraph_element(&elem).unwrap();
+        }
+
+        assert_eq!(elem.to_string(), String::from_utf8(buffer).unwrap());
+    }
+
+    #[test]
+    fn printer_errors_become_fmt_errors() {
+        use std::fmt::Write;
+
+        let elem = ParagraphElement::from(Command::new("bad name"));
+
+        let mut rendered = String::new();
+        assert!(write!(rendered, "{}", elem).is_err());
+
+        let mut buffer = Vec::new();
+        let mut printer = Printer::new(&mut buffer);
+        assert!(printer.visit_paragraph_element(&elem).is_err());
+    }
+}
@@ -1,16 +1,30 @@
 //! A trait which lets you walk your document's AST.
 
+mod alignment;
+mod checklist;
+mod display;
 mod printer;
+mod validate;
 
+pub use self::alignment::{
+    alignment_points, check_alignment, AlignmentChecker, AlignmentIssue, AlignmentProblem,
+};
+pub use self::checklist::{summarise_checklists, ChecklistSummary};
+pub use self::display::FmtWriter;
 pub use self::printer::{print, Printer};
+pub use self::validate::{validate, Diagnostic, Severity, Validator};
 
+use code::CodeBlock;
+use command::Command;
 use document::{Document, DocumentClass, Element, Preamble};
-use equations::{Align, Equation};
+use environment::Environment;
+use equations::{Align, DisplayMath, Equation};
 use failure::Error;
 use lists::{Item, List};
 use paragraph::{Paragraph, ParagraphElement};
 use section::SectionElement;
 use std::ops::Deref;
+use theorem::{Proof, Theorem};
 
 /// A trait which uses the [Visitor Pattern] to recursively visit each node in
 /// a `Document`.
@@ -36,19 +50,31 @@ pub trait Visitor {
     /// `visit_*()` methods.
     ///
     /// > **Note:** You probably don't want to implement this one yourself. If
-    /// you forget to recursively visit each and every variant of `Element`
-    /// you may end up accidentally ignoring half your document!
+    /// > you forget to recursively visit each and every variant of `Element`
+    /// > you may end up accidentally ignoring half your document!
     fn visit_element(&mut self, elem: &Element) -> Result<(), Error> {
         match *elem {
             Element::Para(ref p) => self.visit_paragraph(p)?,
+            Element::Part(ref s) => self.visit_sectioning_element(s)?,
+            Element::Chapter(ref s) => self.visit_sectioning_element(s)?,
             Element::Section(ref s) => self.visit_sectioning_element(s)?,
+            Element::Subsection(ref s) => self.visit_sectioning_element(s)?,
+            Element::Subsubsection(ref s) => self.visit_sectioning_element(s)?,
+            Element::ParagraphSection(ref s) => self.visit_sectioning_element(s)?,
+            Element::Subparagraph(ref s) => self.visit_sectioning_element(s)?,
             Element::UserDefined(ref s) => self.visit_user_defined_line(s)?,
             Element::Align(ref equations) => self.visit_align(equations)?,
+            Element::DisplayMath(ref math) => self.visit_display_math(math)?,
 
             Element::Environment(ref name, ref lines) => {
                 self.visit_custom_environment(name, lines.iter().map(Deref::deref))?
             }
+            Element::Block(ref env) => self.visit_environment(env)?,
             Element::List(ref list) => self.visit_list(list)?,
+            Element::CodeBlock(ref block) => self.visit_code_block(block)?,
+            Element::Command(ref command) => self.visit_command(command)?,
+            Element::Theorem(ref theorem) => self.visit_theorem(theorem)?,
+            Element::Proof(ref proof) => self.visit_proof(proof)?,
             Element::Input(ref s) => self.visit_input(s)?,
 
             _ => {}
@@ -105,6 +131,16 @@ pub trait Visitor {
         Ok(())
     }
 
+    /// Visit a `DisplayMath` block and then recursively visit each equation
+    /// in the block.
+    fn visit_display_math(&mut self, math: &DisplayMath) -> Result<(), Error> {
+        for equation in math.iter() {
+            self.visit_equation(equation)?;
+        }
+
+        Ok(())
+    }
+
     /// Visit a single `Equation`.
     fn visit_equation(&mut self, equation: &Equation) -> Result<(), Error> {
         Ok(())
@@ -119,8 +155,65 @@ pub trait Visitor {
         Ok(())
     }
 
-    /// Visit a single list item.
+    /// Visit a single list item, its text, and then recursively visit each of
+    /// its `Element`s.
     fn visit_list_item(&mut self, item: &Item) -> Result<(), Error> {
+        self.visit_paragraph(&item.content)?;
+
+        for elem in item.iter() {
+            self.visit_element(elem)?;
+        }
+
+        Ok(())
+    }
+
+    /// Visit a block of source code.
+    fn visit_code_block(&mut self, block: &CodeBlock) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Visit a `Command` used as an `Element`, and each of its arguments.
+    ///
+    /// Commands used inline are reached through `visit_paragraph_element()`
+    /// instead.
+    fn visit_command(&mut self, command: &Command) -> Result<(), Error> {
+        for arg in &command.arguments {
+            self.visit_paragraph_element(arg.value())?;
+        }
+
+        Ok(())
+    }
+
+    /// Visit a theorem-like block and then recursively visit each of its
+    /// `Element`s.
+    fn visit_theorem(&mut self, theorem: &Theorem) -> Result<(), Error> {
+        for elem in theorem.iter() {
+            self.visit_element(elem)?;
+        }
+
+        Ok(())
+    }
+
+    /// Visit a `Proof` and then recursively visit each of its `Element`s.
+    fn visit_proof(&mut self, proof: &Proof) -> Result<(), Error> {
+        for elem in proof.iter() {
+            self.visit_element(elem)?;
+        }
+
+        Ok(())
+    }
+
+    /// Visit a generic `Environment`, its arguments, and then recursively
+    /// visit each of its `Element`s.
+    fn visit_environment(&mut self, env: &Environment) -> Result<(), Error> {
+        for arg in env.optional.iter().chain(&env.arguments) {
+            self.visit_paragraph_element(arg)?;
+        }
+
+        for elem in env.iter() {
+            self.visit_element(elem)?;
+        }
+
         Ok(())
     }
 
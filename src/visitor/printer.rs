@@ -1,14 +1,23 @@
 use std::io::Write;
 
 use super::Visitor;
+use code::{CodeBackend, CodeBlock};
+use command::{Argument, Command};
 use document::{Document, DocumentClass, Element, Preamble, PreambleElement};
-use equations::{Align, Equation};
+use environment::Environment;
+use equations::{Align, DisplayMath, Equation, MathEnvironment};
 use failure::Error;
-use lists::{Item, List};
+use length::Length;
+use lists::{CheckboxStyle, Item, List, ListKind};
+use page_style::{HeaderFooter, PageStyle};
 use paragraph::{Paragraph, ParagraphElement};
 use section::SectionElement;
+use theorem::{Proof, Theorem, TheoremStyle};
 
 /// Print a document to a string.
+///
+/// This gives the same output as `doc.to_string()` when the document can be
+/// printed, but reports why it can't instead of panicking.
 pub fn print(doc: &Document) -> Result<String, Error> {
     let mut buffer = Vec::new();
     {
@@ -20,10 +29,29 @@ pub fn print(doc: &Document) -> Result<String, Error> {
     Ok(rendered)
 }
 
+#[cfg(feature = "highlight")]
+fn highlight(code: &str, language: Option<&str>) -> Result<Vec<String>, Error> {
+    ::highlight::highlight(code, language)
+}
+
+#[cfg(not(feature = "highlight"))]
+fn highlight(_code: &str, _language: Option<&str>) -> Result<Vec<String>, Error> {
+    bail!("CodeBackend::Highlighted needs the \"highlight\" feature")
+}
+
+/// Characters which can be used to delimit inline code, in order of
+/// preference.
+const INLINE_CODE_DELIMITERS: &str = "|!+@#~^=/";
+
 /// The type which uses the `Visitor` pattern to visit each node in a document
 /// and write its `tex` representation to a `Writer`.
 pub struct Printer<W> {
     writer: W,
+    list_depth: usize,
+    checklist: Option<CheckboxStyle>,
+    review: bool,
+    code_backend: CodeBackend,
+    qed_here: bool,
 }
 
 impl<W> Printer<W>
@@ -32,7 +60,349 @@ where
 {
     /// Create a new `Printer` which will write to the provided `Writer`.
     pub fn new(writer: W) -> Printer<W> {
-        Printer { writer }
+        Printer {
+            writer,
+            list_depth: 0,
+            checklist: None,
+            review: false,
+            code_backend: CodeBackend::default(),
+            qed_here: false,
+        }
+    }
+
+    /// Set whether todo notes should be rendered. This is normally taken
+    /// from `Document::review` when visiting a `Document`.
+    pub fn review(&mut self, review: bool) -> &mut Self {
+        self.review = review;
+        self
+    }
+
+    /// Set how code should be rendered. This is normally taken from
+    /// `Document::code_backend` when visiting a `Document`.
+    pub fn code_backend(&mut self, backend: CodeBackend) -> &mut Self {
+        self.code_backend = backend;
+        self
+    }
+
+    /// Render something into a string, using a `Printer` with the same
+    /// settings as this one, so it can be inspected before it is written.
+    fn render<F>(&self, visit: F) -> Result<String, Error>
+    where
+        F: FnOnce(&mut Printer<&mut Vec<u8>>) -> Result<(), Error>,
+    {
+        let mut buffer = Vec::new();
+        {
+            let mut printer = Printer {
+                writer: &mut buffer,
+                list_depth: self.list_depth,
+                checklist: self.checklist,
+                review: self.review,
+                code_backend: self.code_backend,
+                qed_here: self.qed_here,
+            };
+            visit(&mut printer)?;
+        }
+
+        Ok(String::from_utf8(buffer)?)
+    }
+
+    /// Write an equation's text followed by its label, tag and `\nonumber`,
+    /// but not the line terminator.
+    fn write_equation(&mut self, equation: &Equation, numbered: bool) -> Result<(), Error> {
+        write!(self.writer, "{}", equation.get_text())?;
+
+        if let Some(label) = equation.get_label() {
+            write!(self.writer, r" \label{{{}}}", label)?;
+        }
+
+        if let Some(tag) = equation.get_tag() {
+            write!(self.writer, r" \tag{{{}}}", tag)?;
+        } else if numbered && !equation.is_numbered() {
+            write!(self.writer, r" \nonumber")?;
+        }
+
+        Ok(())
+    }
+
+    fn write_math_environment(
+        &mut self,
+        environment: &MathEnvironment,
+        numbered: bool,
+        equations: &[&Equation],
+    ) -> Result<(), Error> {
+        match *environment {
+            MathEnvironment::Equation if equations.len() > 1 => bail!(
+                "An `equation` environment can only contain one line, found {}",
+                equations.len()
+            ),
+            MathEnvironment::Alignat(0) => {
+                bail!("An `alignat` environment needs at least one column")
+            }
+            _ => {}
+        }
+
+        let name = environment.environment_name();
+        let star = if numbered { "" } else { "*" };
+
+        write!(self.writer, r"\begin{{{}{}}}", name, star)?;
+        if let MathEnvironment::Alignat(columns) = *environment {
+            write!(self.writer, "{{{}}}", columns)?;
+        }
+        writeln!(self.writer)?;
+
+        match *environment {
+            MathEnvironment::Multline => self.write_multline(equations, numbered)?,
+            _ => {
+                for (i, equation) in equations.iter().enumerate() {
+                    self.write_equation(equation, numbered)?;
+                    self.end_math_line(i + 1 == equations.len())?;
+                }
+            }
+        }
+
+        writeln!(self.writer, r"\end{{{}{}}}", name, star)?;
+
+        Ok(())
+    }
+
+    /// A `multline` is a single equation spread over several lines, so it
+    /// only ever gets one label, tag or `\nonumber` (on the last line).
+    fn write_multline(&mut self, equations: &[&Equation], numbered: bool) -> Result<(), Error> {
+        let labels: Vec<&str> = equations.iter().filter_map(|eq| eq.get_label()).collect();
+        let tags: Vec<&str> = equations.iter().filter_map(|eq| eq.get_tag()).collect();
+
+        if labels.len() > 1 {
+            bail!("A `multline` can only have one label, found {:?}", labels);
+        }
+        if tags.len() > 1 {
+            bail!("A `multline` can only have one tag, found {:?}", tags);
+        }
+
+        let (last, rest) = match equations.split_last() {
+            Some(pair) => pair,
+            None => return Ok(()),
+        };
+
+        for equation in rest {
+            write!(self.writer, "{}", equation.get_text())?;
+            self.end_math_line(false)?;
+        }
+
+        let mut combined = Equation::new(last.get_text());
+        if let Some(label) = labels.first() {
+            combined.label(label);
+        }
+        if let Some(tag) = tags.first() {
+            combined.tag(tag);
+        }
+        if equations.iter().any(|eq| !eq.is_numbered()) {
+            combined.not_numbered();
+        }
+
+        self.write_equation(&combined, numbered)?;
+        self.end_math_line(true)
+    }
+
+    /// Write the body of an environment, with an empty line between each
+    /// element.
+    fn write_body<'a, I>(&mut self, elements: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = &'a Element>,
+    {
+        for (i, element) in elements.into_iter().enumerate() {
+            if i > 0 {
+                writeln!(self.writer)?;
+            }
+            self.visit_element(element)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write a `\newcommand`-style definition, where `start` is everything
+    /// up to the name and each body gets its own braces.
+    fn write_definition(
+        &mut self,
+        start: &str,
+        name: &str,
+        args_num: Option<usize>,
+        default_arg: &Option<String>,
+        bodies: &[&String],
+    ) -> Result<(), Error> {
+        if default_arg.is_some() && args_num.unwrap_or(0) == 0 {
+            bail!(
+                "\\{} doesn't take any arguments, so it can't have a default",
+                name
+            );
+        }
+
+        write!(self.writer, "{}{}}}", start, name)?;
+        if let Some(num) = args_num {
+            write!(self.writer, r"[{}]", num)?;
+        }
+        if let Some(arg) = default_arg {
+            write!(self.writer, r"[{}]", arg)?;
+        }
+        for body in bodies {
+            writeln!(self.writer, r"{{")?;
+            writeln!(self.writer, "{}", body)?;
+            write!(self.writer, r"}}")?;
+        }
+        writeln!(self.writer)?;
+
+        Ok(())
+    }
+
+    /// Write a `\fancypagestyle` definition.
+    fn write_page_style(&mut self, style: &PageStyle) -> Result<(), Error> {
+        // with a separate even header (or footer), the normal one is only
+        // used on odd pages
+        let odd_head = if style.even_header.is_some() { "O" } else { "" };
+        let odd_foot = if style.even_footer.is_some() { "O" } else { "" };
+        let rows = [
+            ("fancyhead", odd_head, Some(&style.header)),
+            ("fancyhead", "E", style.even_header.as_ref()),
+            ("fancyfoot", odd_foot, Some(&style.footer)),
+            ("fancyfoot", "E", style.even_footer.as_ref()),
+        ];
+        let head_rule = rule_width(
+            &[Some(&style.header), style.even_header.as_ref()],
+            &style.head_rule,
+        );
+        let foot_rule = rule_width(
+            &[Some(&style.footer), style.even_footer.as_ref()],
+            &style.foot_rule,
+        );
+        self.write_fancy_page_style(&style.name, &rows, head_rule, foot_rule)?;
+
+        if style.has_first_pages() {
+            let rows = [
+                ("fancyhead", "", style.first_header.as_ref()),
+                ("fancyfoot", "", style.first_footer.as_ref()),
+            ];
+            let head_rule = rule_width(&[style.first_header.as_ref()], &style.head_rule);
+            let foot_rule = rule_width(&[style.first_footer.as_ref()], &style.foot_rule);
+            self.write_fancy_page_style("plain", &rows, head_rule, foot_rule)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_fancy_page_style(
+        &mut self,
+        name: &str,
+        rows: &[(&str, &str, Option<&HeaderFooter>)],
+        head_rule: Option<Length>,
+        foot_rule: Option<Length>,
+    ) -> Result<(), Error> {
+        writeln!(self.writer, r"\fancypagestyle{{{}}}{{", name)?;
+        writeln!(self.writer, r"\fancyhf{{}}")?;
+
+        for &(command, pages, row) in rows {
+            for (position, content) in row.iter().flat_map(|r| r.parts()) {
+                write!(self.writer, r"\{}[{}{}]{{", command, position, pages)?;
+                self.visit_paragraph_element(content)?;
+                writeln!(self.writer, "}}")?;
+            }
+        }
+
+        if let Some(width) = head_rule {
+            writeln!(self.writer, r"\renewcommand{{\headrulewidth}}{{{}}}", width)?;
+        }
+        if let Some(width) = foot_rule {
+            writeln!(self.writer, r"\renewcommand{{\footrulewidth}}{{{}}}", width)?;
+        }
+        writeln!(self.writer, "}}")?;
+
+        Ok(())
+    }
+
+    /// Write an optional argument, bracing it if it contains a `]` which
+    /// would otherwise end the argument early.
+    fn write_optional(&mut self, value: &ParagraphElement) -> Result<(), Error> {
+        let value = self.render(|p| p.visit_paragraph_element(value))?;
+        if value.contains(']') {
+            write!(self.writer, "[{{{}}}]", value)?;
+        } else {
+            write!(self.writer, "[{}]", value)?;
+        }
+        Ok(())
+    }
+
+    /// Write a macro call and its arguments.
+    fn write_command(&mut self, command: &Command) -> Result<(), Error> {
+        if !command.has_valid_name() {
+            bail!("\"{}\" isn't a valid command name", command.name);
+        }
+
+        let star = if command.starred { "*" } else { "" };
+        write!(self.writer, r"\{}{}", command.name, star)?;
+
+        for arg in &command.arguments {
+            match *arg {
+                Argument::Optional(ref value) => self.write_optional(value)?,
+                Argument::Mandatory(ref value) => {
+                    write!(self.writer, "{{")?;
+                    self.visit_paragraph_element(value)?;
+                    write!(self.writer, "}}")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write `\command{element}`.
+    fn write_formatted(&mut self, command: &str, element: &ParagraphElement) -> Result<(), Error> {
+        write!(self.writer, r"\{}{{", command)?;
+        self.visit_paragraph_element(element)?;
+        write!(self.writer, "}}")?;
+        Ok(())
+    }
+
+    /// Write a snippet of inline code, picking a delimiter which doesn't
+    /// appear in the code itself.
+    fn write_inline_code(&mut self, code: &str) -> Result<(), Error> {
+        let delimiter = match INLINE_CODE_DELIMITERS.chars().find(|&c| !code.contains(c)) {
+            Some(c) => c,
+            None => bail!("Unable to find a delimiter for the inline code {:?}", code),
+        };
+        if code.contains('\n') {
+            bail!("Inline code can't span multiple lines");
+        }
+
+        let command = match self.code_backend {
+            CodeBackend::Verbatim | CodeBackend::Highlighted => r"\verb",
+            CodeBackend::Listings => r"\lstinline",
+            CodeBackend::Minted => r"\mintinline{text}",
+        };
+        write!(self.writer, "{}{}{}{}", command, delimiter, code, delimiter)?;
+
+        Ok(())
+    }
+
+    fn end_math_line(&mut self, last: bool) -> Result<(), Error> {
+        if last {
+            if self.qed_here {
+                write!(self.writer, r" \qedhere")?;
+                self.qed_here = false;
+            }
+            writeln!(self.writer)?;
+        } else {
+            writeln!(self.writer, r" \\")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The width of the rule next to a header or footer. There's no point
+/// drawing a rule next to a header or footer which is empty.
+fn rule_width(parts: &[Option<&HeaderFooter>], width: &Option<Length>) -> Option<Length> {
+    if parts.iter().flatten().all(|part| part.is_empty()) {
+        Some(Length::zero())
+    } else {
+        width.clone()
     }
 }
 
@@ -41,6 +411,9 @@ where
     W: Write,
 {
     fn visit_document(&mut self, doc: &Document) -> Result<(), Error> {
+        self.review = doc.review;
+        self.code_backend = doc.code_backend;
+
         match doc.class {
             // only go through childs if we have a partial document
             DocumentClass::Part => {
@@ -50,15 +423,27 @@ where
             }
             // write a full document
             _ => {
+                let options: Vec<String> = doc
+                    .class_options()?
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect();
                 writeln!(
                     self.writer,
                     r"\documentclass[{}]{{{}}}",
-                    doc.arguments.join(","),
+                    options.join(","),
                     doc.class
                 )?;
 
                 self.visit_preamble(&doc.preamble)?;
 
+                if let Some(depth) = doc.toc_depth {
+                    writeln!(self.writer, r"\setcounter{{tocdepth}}{{{}}}", depth)?;
+                }
+                if let Some(depth) = doc.secnum_depth {
+                    writeln!(self.writer, r"\setcounter{{secnumdepth}}{{{}}}", depth)?;
+                }
+
                 writeln!(self.writer, r"\begin{{document}}")?;
 
                 for element in doc.iter() {
@@ -85,6 +470,7 @@ where
             ParagraphElement::Plain(ref s) => write!(self.writer, "{}", s)?,
             ParagraphElement::Link(ref s) => write!(self.writer, "\\href{{{}}}{{{}}}", s.0, s.1)?,
             ParagraphElement::InlineMath(ref s) => write!(self.writer, "${}$", s)?,
+            ParagraphElement::InlineCode(ref s) => self.write_inline_code(s)?,
             ParagraphElement::Bold(ref e) => {
                 write!(self.writer, r"\textbf{{")?;
                 self.visit_paragraph_element(e)?;
@@ -95,12 +481,81 @@ where
                 self.visit_paragraph_element(e)?;
                 write!(self.writer, "}}")?;
             }
+            ParagraphElement::Emph(ref e) => self.write_formatted("emph", e)?,
+            ParagraphElement::Underline(ref e) => self.write_formatted("underline", e)?,
+            ParagraphElement::Monospace(ref e) => self.write_formatted("texttt", e)?,
+            ParagraphElement::SmallCaps(ref e) => self.write_formatted("textsc", e)?,
+            ParagraphElement::StrikeThrough(ref e) => self.write_formatted("sout", e)?,
+            ParagraphElement::Superscript(ref e) => self.write_formatted("textsuperscript", e)?,
+            ParagraphElement::Subscript(ref e) => self.write_formatted("textsubscript", e)?,
+            ParagraphElement::FontSize(ref size, ref e) => {
+                write!(self.writer, r"{{\{} ", size.command_name())?;
+                self.visit_paragraph_element(e)?;
+                write!(self.writer, "}}")?;
+            }
+            ParagraphElement::Span(ref elems) => {
+                for e in elems {
+                    self.visit_paragraph_element(e)?;
+                }
+            }
+            ParagraphElement::Footnote(ref e) => self.write_formatted("footnote", e)?,
+            ParagraphElement::FootnoteMark => write!(self.writer, r"\footnotemark{{}}")?,
+            ParagraphElement::FootnoteText(ref e) => self.write_formatted("footnotetext", e)?,
+            ParagraphElement::MarginNote(ref e) => self.write_formatted("marginpar", e)?,
+            ParagraphElement::Command(ref command) => {
+                self.write_command(command)?;
+                // stop a macro without arguments from swallowing the
+                // following space or letters
+                let letters = command.name.chars().all(|c| c.is_ascii_alphabetic());
+                if command.arguments.is_empty() && letters {
+                    write!(self.writer, "{{}}")?;
+                }
+            }
+            ParagraphElement::HSpace(ref length) => write!(self.writer, r"\hspace{{{}}}", length)?,
+            ParagraphElement::HFill => write!(self.writer, r"\hfill{{}}")?,
+            ParagraphElement::LineBreak => write!(self.writer, r"\linebreak{{}}")?,
+            ParagraphElement::NonBreakingSpace => write!(self.writer, "~")?,
+            ParagraphElement::Colored(ref color, ref e) => {
+                write!(self.writer, r"\textcolor{}{{", color.to_argument()?)?;
+                self.visit_paragraph_element(e)?;
+                write!(self.writer, "}}")?;
+            }
+            ParagraphElement::ColorBox(ref background, ref e) => {
+                write!(self.writer, r"\colorbox{}{{", background.to_argument()?)?;
+                self.visit_paragraph_element(e)?;
+                write!(self.writer, "}}")?;
+            }
+            ParagraphElement::FramedColorBox(ref frame, ref background, ref e) => {
+                let model = match (frame.model(), background.model()) {
+                    (None, None) => String::new(),
+                    (Some(a), Some(b)) if a == b => format!("[{}]", a),
+                    _ => bail!(
+                        "The frame and background of an \\fcolorbox must use the same colour model"
+                    ),
+                };
+                write!(
+                    self.writer,
+                    r"\fcolorbox{}{{{}}}{{{}}}{{",
+                    model,
+                    frame.spec()?,
+                    background.spec()?
+                )?;
+                self.visit_paragraph_element(e)?;
+                write!(self.writer, "}}")?;
+            }
+            ParagraphElement::Todo(ref e) => {
+                if self.review {
+                    self.write_formatted("todo", e)?;
+                }
+            }
         }
 
         Ok(())
     }
 
     fn visit_preamble(&mut self, preamble: &Preamble) -> Result<(), Error> {
+        let mut theorem_style = TheoremStyle::Plain;
+
         for item in preamble.iter() {
             match item {
                 PreambleElement::UsePackage {
@@ -116,18 +571,100 @@ where
                     args_num,
                     default_arg,
                     definition,
-                } => {
-                    write!(self.writer, r"\newcommand{{\{}}}", name)?;
-                    if let Some(num) = args_num {
-                        write!(self.writer, r"[{}]", num)?;
-                    }
-                    if let Some(arg) = default_arg {
-                        write!(self.writer, r"[{}]", arg)?;
+                } => self.write_definition(
+                    r"\newcommand{\",
+                    name,
+                    *args_num,
+                    default_arg,
+                    &[definition],
+                )?,
+                PreambleElement::RenewCommand {
+                    name,
+                    args_num,
+                    default_arg,
+                    definition,
+                } => self.write_definition(
+                    r"\renewcommand{\",
+                    name,
+                    *args_num,
+                    default_arg,
+                    &[definition],
+                )?,
+                PreambleElement::ProvideCommand {
+                    name,
+                    args_num,
+                    default_arg,
+                    definition,
+                } => self.write_definition(
+                    r"\providecommand{\",
+                    name,
+                    *args_num,
+                    default_arg,
+                    &[definition],
+                )?,
+                PreambleElement::NewEnvironment {
+                    name,
+                    args_num,
+                    default_arg,
+                    begin,
+                    end,
+                } => self.write_definition(
+                    r"\newenvironment{",
+                    name,
+                    *args_num,
+                    default_arg,
+                    &[begin, end],
+                )?,
+                PreambleElement::DeclareMathOperator {
+                    name,
+                    definition,
+                    limits,
+                } => writeln!(
+                    self.writer,
+                    r"\DeclareMathOperator{}{{\{}}}{{{}}}",
+                    if *limits { "*" } else { "" },
+                    name,
+                    definition
+                )?,
+                PreambleElement::NewTheorem(theorem) => {
+                    // check the counters before anything is written
+                    let numbering = match (&theorem.shared_with, &theorem.number_within) {
+                        (Some(_), Some(_)) => bail!(
+                            "The \"{}\" theorem can't both share a counter and be numbered within another",
+                            theorem.name
+                        ),
+                        (Some(_), None) | (None, Some(_)) if !theorem.numbered => bail!(
+                            "The \"{}\" theorem is unnumbered, so it can't use a counter",
+                            theorem.name
+                        ),
+                        (Some(shared), None) => format!("[{}]{{{}}}", shared, theorem.title),
+                        (None, Some(within)) => format!("{{{}}}[{}]", theorem.title, within),
+                        (None, None) => format!("{{{}}}", theorem.title),
+                    };
+
+                    if theorem.style != theorem_style {
+                        writeln!(self.writer, r"\theoremstyle{{{}}}", theorem.style.name())?;
+                        theorem_style = theorem.style;
                     }
-                    writeln!(self.writer, r"{{")?;
-                    writeln!(self.writer, "{}", definition)?;
-                    writeln!(self.writer, r"}}")?;
+
+                    let star = if theorem.numbered { "" } else { "*" };
+                    writeln!(
+                        self.writer,
+                        r"\newtheorem{}{{{}}}{}",
+                        star, theorem.name, numbering
+                    )?;
                 }
+                PreambleElement::DefineColor { name, color } => match color.model() {
+                    Some(model) => writeln!(
+                        self.writer,
+                        r"\definecolor{{{}}}{{{}}}{{{}}}",
+                        name,
+                        model,
+                        color.spec()?
+                    )?,
+                    None => writeln!(self.writer, r"\colorlet{{{}}}{{{}}}", name, color.spec()?)?,
+                },
+                PreambleElement::PageStyle(style) => self.write_page_style(style)?,
                 PreambleElement::UserDefined(s) => writeln!(self.writer, r"{}", s)?,
             }
         }
@@ -148,20 +685,63 @@ where
 
     fn visit_list(&mut self, list: &List) -> Result<(), Error> {
         let env = list.kind.environment_name();
+        // nested lists are indented so the structure is easy to follow
+        let indent = "  ".repeat(self.list_depth);
 
-        writeln!(self.writer, r"\begin{{{}}}", env)?;
+        write!(self.writer, r"{}\begin{{{}}}", indent, env)?;
+        if !list.options.is_empty() {
+            write!(self.writer, "[{}]", list.options)?;
+        }
+        writeln!(self.writer)?;
 
+        let outer_checklist = self.checklist;
+        self.checklist = match list.kind {
+            ListKind::Checklist(style) => Some(style),
+            _ => None,
+        };
+
+        self.list_depth += 1;
         for item in list.iter() {
             self.visit_list_item(item)?;
         }
+        self.list_depth -= 1;
+        self.checklist = outer_checklist;
 
-        writeln!(self.writer, r"\end{{{}}}", env)?;
+        writeln!(self.writer, r"{}\end{{{}}}", indent, env)?;
 
         Ok(())
     }
 
     fn visit_list_item(&mut self, item: &Item) -> Result<(), Error> {
-        writeln!(self.writer, r"\item {}", item.0)?;
+        let indent = "  ".repeat(self.list_depth.saturating_sub(1));
+
+        write!(self.writer, r"{}\item", indent)?;
+        if let Some(ref label) = item.label {
+            let label = self.render(|p| p.visit_paragraph_element(label))?;
+            if label.contains(',') || label.contains(']') {
+                write!(self.writer, "[{{{}}}]", label)?;
+            } else {
+                write!(self.writer, "[{}]", label)?;
+            }
+        } else if item.has_checkbox(self.checklist.is_some()) {
+            let style = self.checklist.unwrap_or_default();
+            let done = item.checked.unwrap_or(false);
+            write!(self.writer, "[{}]", style.symbol(done))?;
+        }
+        write!(self.writer, " ")?;
+        for elem in item.content.iter() {
+            self.visit_paragraph_element(elem)?;
+        }
+        writeln!(self.writer)?;
+
+        for element in item.iter() {
+            if let Element::Para(_) = *element {
+                // separate the item's paragraphs with a blank line
+                writeln!(self.writer)?;
+            }
+            self.visit_element(element)?;
+        }
+
         Ok(())
     }
 
@@ -173,11 +753,31 @@ where
             Element::Section(ref s) => self.visit_sectioning_element(s)?,
             Element::Subsection(ref s) => self.visit_sectioning_element(s)?,
             Element::Subsubsection(ref s) => self.visit_sectioning_element(s)?,
+            Element::ParagraphSection(ref s) => self.visit_sectioning_element(s)?,
+            Element::Subparagraph(ref s) => self.visit_sectioning_element(s)?,
             Element::TableOfContents => writeln!(self.writer, r"\tableofcontents")?,
             Element::TitlePage => writeln!(self.writer, r"\maketitle")?,
             Element::ClearPage => writeln!(self.writer, r"\clearpage")?,
+            Element::NewPage => writeln!(self.writer, r"\newpage")?,
+            Element::PageBreak => writeln!(self.writer, r"\pagebreak")?,
+            Element::VSpace(ref length) => writeln!(self.writer, r"\vspace{{{}}}", length)?,
+            Element::VFill => writeln!(self.writer, r"\vfill")?,
+            Element::SmallSkip => writeln!(self.writer, r"\smallskip")?,
+            Element::MedSkip => writeln!(self.writer, r"\medskip")?,
+            Element::BigSkip => writeln!(self.writer, r"\bigskip")?,
+            Element::NewGeometry(ref geometry) => writeln!(
+                self.writer,
+                r"\newgeometry{{{}}}",
+                geometry.layout_options().join(",")
+            )?,
+            Element::RestoreGeometry => writeln!(self.writer, r"\restoregeometry")?,
+            Element::PageStyle(ref name) => writeln!(self.writer, r"\pagestyle{{{}}}", name)?,
+            Element::ThisPageStyle(ref name) => {
+                writeln!(self.writer, r"\thispagestyle{{{}}}", name)?
+            }
             Element::UserDefined(ref s) => writeln!(self.writer, "{}", s)?,
             Element::Align(ref equations) => self.visit_align(equations)?,
+            Element::DisplayMath(ref math) => self.visit_display_math(math)?,
 
             Element::Environment(ref name, ref lines) => {
                 writeln!(self.writer, r"\begin{{{}}}", name)?;
@@ -186,7 +786,12 @@ where
                 }
                 writeln!(self.writer, r"\end{{{}}}", name)?;
             }
+            Element::Block(ref env) => self.visit_environment(env)?,
             Element::List(ref list) => self.visit_list(list)?,
+            Element::CodeBlock(ref block) => self.visit_code_block(block)?,
+            Element::Command(ref command) => self.visit_command(command)?,
+            Element::Theorem(ref theorem) => self.visit_theorem(theorem)?,
+            Element::Proof(ref proof) => self.visit_proof(proof)?,
             Element::Input(ref s) => writeln!(self.writer, "\\input{{{}}}", s)?,
 
             Element::_Other => unreachable!(),
@@ -195,18 +800,165 @@ where
         Ok(())
     }
 
+    fn visit_code_block(&mut self, block: &CodeBlock) -> Result<(), Error> {
+        let environment = match self.code_backend {
+            CodeBackend::Verbatim => "verbatim",
+            CodeBackend::Listings => "lstlisting",
+            CodeBackend::Minted => "minted",
+            CodeBackend::Highlighted => "Verbatim",
+        };
+        let end = format!(r"\end{{{}}}", environment);
+        if block.code.contains(&end) {
+            bail!("A code block can't contain \"{}\"", end);
+        }
+        if self.code_backend == CodeBackend::Listings && !block.highlight_lines.is_empty() {
+            bail!("The listings backend can't highlight lines, use CodeBackend::Minted instead");
+        }
+
+        match self.code_backend {
+            CodeBackend::Verbatim => writeln!(self.writer, r"\begin{{verbatim}}")?,
+            CodeBackend::Listings => {
+                let mut options = Vec::new();
+                if let Some(ref label) = block.label {
+                    options.push(format!("label={}", label));
+                }
+                if block.line_numbers {
+                    options.push(String::from("numbers=left"));
+                }
+                if let Some(first) = block.first_line {
+                    options.push(format!("firstnumber={}", first));
+                }
+
+                write!(self.writer, r"\begin{{lstlisting}}")?;
+                let mut separator = "[";
+                if let Some(ref language) = block.language {
+                    write!(self.writer, "{}language={}", separator, language)?;
+                    separator = ", ";
+                }
+                if let Some(ref caption) = block.caption {
+                    write!(self.writer, "{}caption={{", separator)?;
+                    self.visit_paragraph_element(caption)?;
+                    write!(self.writer, "}}")?;
+                    separator = ", ";
+                }
+                for option in options {
+                    write!(self.writer, "{}{}", separator, option)?;
+                    separator = ", ";
+                }
+                if separator != "[" {
+                    write!(self.writer, "]")?;
+                }
+                writeln!(self.writer)?;
+            }
+            CodeBackend::Minted => {
+                if block.caption.is_some() || block.label.is_some() {
+                    writeln!(self.writer, r"\begin{{listing}}")?;
+                }
+
+                let mut options = Vec::new();
+                if block.line_numbers {
+                    options.push(String::from("linenos"));
+                }
+                if let Some(first) = block.first_line {
+                    options.push(format!("firstnumber={}", first));
+                }
+                if !block.highlight_lines.is_empty() {
+                    let lines: Vec<String> = block
+                        .highlight_lines
+                        .iter()
+                        .map(|l| l.to_string())
+                        .collect();
+                    options.push(format!("highlightlines={{{}}}", lines.join(",")));
+                }
+
+                write!(self.writer, r"\begin{{minted}}")?;
+                if !options.is_empty() {
+                    write!(self.writer, "[{}]", options.join(", "))?;
+                }
+                writeln!(
+                    self.writer,
+                    "{{{}}}",
+                    block.language.as_deref().unwrap_or("text")
+                )?;
+            }
+            CodeBackend::Highlighted => {
+                let lines = highlight(&block.code, block.language.as_deref())?;
+
+                let mut options = vec![String::from(r"commandchars=\\\{\}")];
+                if block.line_numbers {
+                    options.push(String::from("numbers=left"));
+                }
+                if let Some(first) = block.first_line {
+                    options.push(format!("firstnumber={}", first));
+                }
+                writeln!(self.writer, r"\begin{{Verbatim}}[{}]", options.join(", "))?;
+
+                for line in lines {
+                    writeln!(self.writer, "{}", line)?;
+                }
+                writeln!(self.writer, "{}", end)?;
+
+                return Ok(());
+            }
+        }
+
+        writeln!(self.writer, "{}", block.code.trim_end_matches('\n'))?;
+        writeln!(self.writer, "{}", end)?;
+
+        if self.code_backend == CodeBackend::Minted
+            && (block.caption.is_some() || block.label.is_some())
+        {
+            if let Some(ref caption) = block.caption {
+                self.write_formatted("caption", caption)?;
+                writeln!(self.writer)?;
+            }
+            if let Some(ref label) = block.label {
+                writeln!(self.writer, r"\label{{{}}}", label)?;
+            }
+            writeln!(self.writer, r"\end{{listing}}")?;
+        }
+
+        Ok(())
+    }
+
     fn visit_sectioning_element<T: SectionElement>(&mut self, section: &T) -> Result<(), Error> {
         write!(
             self.writer,
-            r"\{}{}{{",
+            r"\{}{}",
             section.get_section_name(),
             match section.numbered() {
                 true => "",
                 false => "*",
             }
         )?;
+        // starred sections can't take a short name
+        if let (Some(short_name), true) = (section.get_short_name(), section.numbered()) {
+            let short_name = self.render(|p| p.visit_paragraph_element(short_name))?;
+            // a `]` would otherwise end the optional argument early
+            if short_name.contains(']') {
+                write!(self.writer, "[{{{}}}]", short_name)?;
+            } else {
+                write!(self.writer, "[{}]", short_name)?;
+            }
+        }
+        write!(self.writer, "{{")?;
         self.visit_paragraph_element(section.get_name())?;
-        writeln!(self.writer, "}}")?;
+        write!(self.writer, "}}")?;
+        if let Some(label) = section.get_label() {
+            write!(self.writer, r"\label{{{}}}", label)?;
+        }
+        writeln!(self.writer)?;
+
+        if !section.numbered() && section.in_toc() {
+            write!(
+                self.writer,
+                r"\addcontentsline{{toc}}{{{}}}{{",
+                section.get_section_name()
+            )?;
+            let toc_name = section.get_short_name().unwrap_or(section.get_name());
+            self.visit_paragraph_element(toc_name)?;
+            writeln!(self.writer, "}}")?;
+        }
 
         if !section.is_empty() {
             // Make sure there's space between the \section{...} and the next line
@@ -223,40 +975,111 @@ where
         Ok(())
     }
 
-    fn visit_equation(&mut self, equation: &Equation) -> Result<(), Error> {
-        write!(self.writer, r"{}", equation.get_text())?;
+    fn visit_command(&mut self, command: &Command) -> Result<(), Error> {
+        self.write_command(command)?;
+        writeln!(self.writer)?;
+        Ok(())
+    }
 
-        if let Some(ref label) = equation.get_label() {
-            write!(self.writer, r" \label{{{}}}", label)?;
+    fn visit_environment(&mut self, env: &Environment) -> Result<(), Error> {
+        write!(self.writer, r"\begin{{{}}}", env.name)?;
+        if let Some(ref optional) = env.optional {
+            self.write_optional(optional)?;
         }
-        if !equation.is_numbered() {
-            write!(self.writer, r" \nonumber")?;
+        for arg in &env.arguments {
+            write!(self.writer, "{{")?;
+            self.visit_paragraph_element(arg)?;
+            write!(self.writer, "}}")?;
         }
+        writeln!(self.writer)?;
+
+        self.write_body(env.iter())?;
 
-        writeln!(self.writer, r" \\")?;
+        writeln!(self.writer, r"\end{{{}}}", env.name)?;
         Ok(())
     }
 
-    fn visit_align(&mut self, align: &Align) -> Result<(), Error> {
-        writeln!(self.writer, r"\begin{{align}}")?;
-
-        for item in align.iter() {
-            self.visit_equation(item)?;
+    fn visit_theorem(&mut self, theorem: &Theorem) -> Result<(), Error> {
+        write!(self.writer, r"\begin{{{}}}", theorem.environment)?;
+        if let Some(ref title) = theorem.title {
+            self.write_optional(title)?;
         }
+        if let Some(ref label) = theorem.label {
+            write!(self.writer, r"\label{{{}}}", label)?;
+        }
+        writeln!(self.writer)?;
 
-        writeln!(self.writer, r"\end{{align}}")?;
+        self.write_body(theorem.iter())?;
 
+        writeln!(self.writer, r"\end{{{}}}", theorem.environment)?;
         Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use self::ParagraphElement::*;
-    use super::*;
-    use {Align, Chapter, DocumentClass, Equation, ListKind, Paragraph, Part, Section, Subsection};
+    fn visit_proof(&mut self, proof: &Proof) -> Result<(), Error> {
+        write!(self.writer, r"\begin{{proof}}")?;
+        if let Some(ref title) = proof.title {
+            self.write_optional(title)?;
+        }
+        writeln!(self.writer)?;
 
-    #[test]
+        if let Some((last, rest)) = proof.iter().as_slice().split_last() {
+            self.write_body(rest)?;
+            if !rest.is_empty() {
+                writeln!(self.writer)?;
+            }
+
+            self.qed_here = proof.ends_with_math();
+            let result = self.visit_element(last);
+            self.qed_here = false;
+            result?;
+        }
+
+        writeln!(self.writer, r"\end{{proof}}")?;
+        Ok(())
+    }
+
+    fn visit_equation(&mut self, equation: &Equation) -> Result<(), Error> {
+        self.write_equation(equation, true)?;
+        writeln!(self.writer)?;
+        Ok(())
+    }
+
+    fn visit_align(&mut self, align: &Align) -> Result<(), Error> {
+        let equations: Vec<&Equation> = align.iter().collect();
+        self.write_math_environment(&MathEnvironment::Align, true, &equations)
+    }
+
+    fn visit_display_math(&mut self, math: &DisplayMath) -> Result<(), Error> {
+        if math.is_grouped() {
+            writeln!(self.writer, r"\begin{{subequations}}")?;
+            if let Some(label) = math.get_group_label() {
+                writeln!(self.writer, r"\label{{{}}}", label)?;
+            }
+        }
+
+        let equations: Vec<&Equation> = math.iter().collect();
+        self.write_math_environment(&math.environment, math.numbered, &equations)?;
+
+        if math.is_grouped() {
+            writeln!(self.writer, r"\end{{subequations}}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use self::ParagraphElement::*;
+    use super::*;
+    use {
+        Align, Cases, Chapter, ClassOption, CodeBackend, CodeBlock, Color, Command, DisplayMath,
+        DocumentClass, Equation, FontSize, HeaderFooter, Length, ListKind, MathEnvironment,
+        Numbering, PageGeometry, PaperSize, Paragraph, ParagraphSection, Part, PointSize, Section,
+        Split, Subparagraph, Subsection, Subsubsection, TheoremDefinition,
+    };
+
+    #[test]
     fn create_simple_paragraph() {
         let should_be = "Hello World\n";
         let mut buffer = Vec::new();
@@ -306,6 +1129,88 @@ mod tests {
         assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
     }
 
+    #[test]
+    fn paragraph_with_rich_formatting() {
+        let should_be = "\\emph{a} \\underline{b} \\texttt{c} \\textsc{d} \\sout{e} \\textsuperscript{f}\\textsubscript{g} {\\Large \\textbf{Big \\textit{and} bold}}\n";
+        let mut buffer = Vec::new();
+
+        let mut para = Paragraph::new();
+        para.push(ParagraphElement::emph("a"))
+            .push(" ")
+            .push(ParagraphElement::underline("b"))
+            .push(" ")
+            .push(ParagraphElement::monospace("c"))
+            .push(" ")
+            .push(ParagraphElement::small_caps("d"))
+            .push(" ")
+            .push(ParagraphElement::strike_through("e"))
+            .push(" ")
+            .push(ParagraphElement::superscript("f"))
+            .push(ParagraphElement::subscript("g"))
+            .push(" ")
+            .push(ParagraphElement::sized(
+                FontSize::XLarge,
+                ParagraphElement::bold(vec![
+                    Plain("Big ".to_string()),
+                    ParagraphElement::italic("and"),
+                    Plain(" bold".to_string()),
+                ]),
+            ));
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_paragraph(&para).unwrap();
+        }
+
+        let rendered = String::from_utf8(buffer).unwrap();
+        assert_eq!(rendered, should_be);
+
+        // the Display impl should always agree with the printer
+        let displayed: String = para.iter().map(|e| e.to_string()).collect();
+        assert_eq!(format!("{}\n", displayed), rendered);
+    }
+
+    #[test]
+    fn paragraph_with_footnotes_and_margin_notes() {
+        let should_be = "A\\footnote{\\textit{note}} B\\footnotemark{} C\\footnotetext{later}\\marginpar{aside}\n";
+        let mut buffer = Vec::new();
+
+        let mut para = Paragraph::new();
+        para.push("A")
+            .push(ParagraphElement::footnote(ParagraphElement::italic("note")))
+            .push(" B")
+            .push(FootnoteMark)
+            .push(" C")
+            .push(FootnoteText(Box::new("later".into())))
+            .push(ParagraphElement::margin_note("aside"));
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_paragraph(&para).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn todo_notes_only_shown_in_review_mode() {
+        let mut section = Section::new("Draft");
+        let mut para = Paragraph::from("Text");
+        para.push(ParagraphElement::todo("Check this"));
+        section.push(para);
+
+        let mut doc = Document::new(DocumentClass::Part);
+        doc.push(section);
+
+        assert_eq!(doc.to_string(), "\\section{Draft}\n\nText\n\n");
+
+        doc.review = true;
+        assert_eq!(
+            doc.to_string(),
+            "\\section{Draft}\n\nText\\todo{Check this}\n\n"
+        );
+    }
+
     #[test]
     fn inline_code() {
         let should_be = "Hello $\\lambda$ World!\n";
@@ -324,6 +1229,261 @@ mod tests {
         assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
     }
 
+    #[test]
+    fn inline_code_with_each_backend() {
+        let mut para = Paragraph::from("Call ");
+        para.push(InlineCode(String::from("a | b")));
+
+        let inputs = vec![
+            (CodeBackend::Verbatim, "Call \\verb!a | b!\n"),
+            (CodeBackend::Listings, "Call \\lstinline!a | b!\n"),
+            (CodeBackend::Minted, "Call \\mintinline{text}!a | b!\n"),
+        ];
+
+        for (backend, should_be) in inputs {
+            let mut buffer = Vec::new();
+            {
+                let mut printer = Printer::new(&mut buffer);
+                printer.code_backend(backend);
+                printer.visit_paragraph(&para).unwrap();
+            }
+
+            assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+        }
+    }
+
+    #[test]
+    fn render_verbatim_code_block() {
+        let should_be = "\\begin{verbatim}\nx = 1\nprint(x)\n\\end{verbatim}\n";
+        let mut buffer = Vec::new();
+
+        let mut block = CodeBlock::new("x = 1\nprint(x)\n");
+        block.language("Python").caption("Ignored");
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_code_block(&block).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn render_listings_code_block() {
+        let should_be = r#"\documentclass[]{article}
+\usepackage{listings}
+\begin{document}
+\begin{lstlisting}[language=Python, caption={Setting \textbf{x}}, label=lst:x, numbers=left, firstnumber=10]
+x = 1
+\end{lstlisting}
+\end{document}
+"#;
+
+        let mut block = CodeBlock::new("x = 1");
+        block
+            .language("Python")
+            .caption(ParagraphElement::span(vec![
+                ParagraphElement::from("Setting "),
+                ParagraphElement::bold("x"),
+            ]))
+            .label("lst:x")
+            .line_numbers()
+            .first_line(10);
+
+        let mut doc = Document::new(DocumentClass::Article);
+        doc.use_code_backend(CodeBackend::Listings);
+        doc.push(block);
+
+        assert_eq!(print(&doc).unwrap(), should_be);
+    }
+
+    #[test]
+    fn listings_cant_highlight_lines() {
+        let mut block = CodeBlock::new("x = 1\ny = 2");
+        block.highlight(vec![2]);
+
+        let mut buffer = Vec::new();
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.code_backend(CodeBackend::Listings);
+            assert!(printer.visit_code_block(&block).is_err());
+        }
+
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn listings_caption_errors_are_reported() {
+        let mut block = CodeBlock::new("x = 1");
+        block.caption(Command::new("bad name"));
+
+        let mut buffer = Vec::new();
+        let mut printer = Printer::new(&mut buffer);
+        printer.code_backend(CodeBackend::Listings);
+        assert!(printer.visit_code_block(&block).is_err());
+    }
+
+    #[test]
+    fn render_minted_code_block() {
+        let should_be = r#"\begin{listing}
+\begin{minted}[linenos, highlightlines={2,3}]{rust}
+fn main() {
+    println!("Hello");
+}
+\end{minted}
+\caption{Hello World}
+\label{lst:hello}
+\end{listing}
+"#;
+        let mut buffer = Vec::new();
+
+        let mut block = CodeBlock::new("fn main() {\n    println!(\"Hello\");\n}");
+        block
+            .language("rust")
+            .caption("Hello World")
+            .label("lst:hello")
+            .line_numbers()
+            .highlight(vec![2, 3]);
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.code_backend(CodeBackend::Minted);
+            printer.visit_code_block(&block).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[cfg(feature = "highlight")]
+    #[test]
+    fn render_highlighted_code_block() {
+        let should_be = r#"\begin{Verbatim}[commandchars=\\\{\}, numbers=left]
+\textcolor[RGB]{167,29,93}{\textbf{let}} s \textcolor[RGB]{167,29,93}{\textbf{=}} \textcolor[RGB]{24,54,145}{"}\textcolor[RGB]{24,54,145}{\char123{}\char125{}}\textcolor[RGB]{24,54,145}{"};
+\end{Verbatim}
+"#;
+        let mut buffer = Vec::new();
+
+        let mut block = CodeBlock::new("let s = \"{}\";");
+        block.language("Rust").line_numbers();
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.code_backend(CodeBackend::Highlighted);
+            printer.visit_code_block(&block).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[cfg(not(feature = "highlight"))]
+    #[test]
+    fn highlighting_needs_the_feature() {
+        let mut buffer = Vec::new();
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.code_backend(CodeBackend::Highlighted);
+            assert!(printer.visit_code_block(&CodeBlock::new("x")).is_err());
+        }
+
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn code_block_cant_contain_its_end_marker() {
+        let block = CodeBlock::new("\\end{verbatim}");
+        let mut printer = Printer::new(Vec::new());
+
+        assert!(printer.visit_code_block(&block).is_err());
+    }
+
+    #[test]
+    fn use_code_backend_only_adds_the_package_once() {
+        let mut doc = Document::default();
+        doc.use_code_backend(CodeBackend::Minted)
+            .use_code_backend(CodeBackend::Minted);
+
+        assert_eq!(doc.preamble.iter().count(), 1);
+        assert!(doc.preamble.has_package("minted"));
+    }
+
+    #[test]
+    fn inline_commands() {
+        let should_be = "\\LaTeX{} is \\textcolor{red}{\\emph{great}}\\,!\n";
+        let mut buffer = Vec::new();
+
+        let mut colour = Command::new("textcolor");
+        colour
+            .argument("red")
+            .argument(ParagraphElement::emph("great"));
+        let mut para = Paragraph::new();
+        para.push(Command::new("LaTeX"))
+            .push(" is ")
+            .push(colour)
+            .push(Command::new(","))
+            .push("!");
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_paragraph(&para).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn commands_dont_swallow_following_letters() {
+        let should_be = "\\i{}tem \\'e\n";
+        let mut buffer = Vec::new();
+
+        let mut para = Paragraph::new();
+        para.push(Command::new("i"))
+            .push("tem ")
+            .push(Command::new("'"))
+            .push("e");
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_paragraph(&para).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn optional_argument_errors_are_reported() {
+        let mut command = Command::new("item");
+        command.optional(Command::new("bad name"));
+
+        let mut printer = Printer::new(Vec::new());
+        assert!(printer.visit_command(&command).is_err());
+    }
+
+    #[test]
+    fn render_command_elements() {
+        let should_be = "\\vspace*{1em}\n\\includepdf[pages=-]{x.pdf}\n";
+        let mut buffer = Vec::new();
+
+        let mut space = Command::new("vspace");
+        space.starred().argument("1em");
+        let mut include = Command::new("includepdf");
+        include.optional("pages=-").argument("x.pdf");
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_element(&space.into()).unwrap();
+            printer.visit_element(&include.into()).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn invalid_command_names_are_an_error() {
+        let mut printer = Printer::new(Vec::new());
+
+        assert!(printer.visit_command(&Command::new("not valid")).is_err());
+    }
+
     #[test]
     fn preamble_with_author_and_title() {
         let should_be = r#"\title{Sample Document}
@@ -343,114 +1503,812 @@ mod tests {
     }
 
     #[test]
-    fn preamble_with_title_and_package_imports() {
-        let should_be = r#"\usepackage{amsmath}
-\usepackage{graphics}
+    fn preamble_with_title_and_package_imports() {
+        let should_be = r#"\usepackage{amsmath}
+\usepackage{graphics}
+
+\title{Sample Document}
+"#;
+        let mut buffer = Vec::new();
+
+        let mut preamble = Preamble::default();
+        preamble
+            .title("Sample Document")
+            .use_package("amsmath")
+            .use_package("graphics");
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_preamble(&preamble).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn preamble_with_newcommand() {
+        let should_be = r#"\newcommand{\Love}[2]{
+#1 loves #2
+}
+"#;
+        let mut buffer = Vec::new();
+        let mut preamble = Preamble::default();
+        preamble.new_command("Love", 2, "#1 loves #2");
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_preamble(&preamble).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn preamble_with_newcommand_with_default_argument() {
+        let should_be = r#"\newcommand{\Love}[3][likes]{
+#2 #1 #3
+}
+"#;
+        let mut buffer = Vec::new();
+        let mut preamble = Preamble::default();
+        preamble.push(PreambleElement::NewCommand {
+            name: String::from("Love"),
+            args_num: Some(3),
+            default_arg: Some(String::from("likes")),
+            definition: String::from("#2 #1 #3"),
+        });
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_preamble(&preamble).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn preamble_with_other_definitions() {
+        let should_be = r#"\renewcommand{\vec}[1]{
+\mathbf{#1}
+}
+\providecommand{\R}[0]{
+\mathbb{R}
+}
+\newenvironment{boxed}[1]{
+\begin{center}\fbox{#1}
+}{
+\end{center}
+}
+\usepackage{amsmath}
+\DeclareMathOperator{\Tr}{Tr}
+\DeclareMathOperator*{\argmax}{arg\,max}
+"#;
+        let mut buffer = Vec::new();
+        let mut preamble = Preamble::default();
+        preamble.renew_command("vec", 1, r"\mathbf{#1}");
+        preamble.provide_command("R", 0, r"\mathbb{R}");
+        preamble.new_environment("boxed", 1, r"\begin{center}\fbox{#1}", r"\end{center}");
+        let trace = preamble.declare_math_operator("Tr", "Tr");
+        preamble.declare_math_operator_with_limits("argmax", r"arg\,max");
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_preamble(&preamble).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+        assert_eq!(trace.invoke().unwrap().to_string(), "\\Tr\n");
+    }
+
+    #[test]
+    fn call_macros_defined_in_the_preamble() {
+        let mut preamble = Preamble::default();
+        let love = preamble
+            .new_command_with_default("Love", 3, "likes", "#2 #1 #3")
+            .unwrap();
+        let boxed = preamble.new_environment("boxed", 1, r"\fbox{#1}", "");
+
+        let mut para = Paragraph::new();
+        para.push(love.call(vec!["Alice", "Bob"]).unwrap())
+            .push(" and ")
+            .push(love.call(vec!["adores", "Bob", "Alice"]).unwrap());
+        let mut env = boxed.begin(vec!["Note"]).unwrap();
+        env.push(para);
+
+        assert_eq!(
+            env.to_string(),
+            "\\begin{boxed}{Note}\n\\Love{Alice}{Bob} and \\Love[adores]{Bob}{Alice}\n\\end{boxed}\n"
+        );
+        assert!(love.call(vec!["Alice"]).is_err());
+    }
+
+    #[test]
+    fn default_argument_needs_an_argument() {
+        let mut preamble = Preamble::default();
+        assert!(preamble
+            .new_command_with_default("R", 0, "x", r"\mathbb{R}")
+            .is_err());
+        assert!(preamble.is_empty());
+
+        preamble.push(PreambleElement::NewCommand {
+            name: String::from("R"),
+            args_num: None,
+            default_arg: Some(String::from("x")),
+            definition: String::from(r"\mathbb{R}"),
+        });
+        let mut buffer = Vec::new();
+        {
+            let mut printer = Printer::new(&mut buffer);
+            assert!(printer.visit_preamble(&preamble).is_err());
+        }
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn render_environment_with_nested_elements() {
+        let should_be = r#"\begin{minipage}[t]{0.5\textwidth}
+Some text
+
+\begin{itemize}
+\item First
+\item Second
+\end{itemize}
+\end{minipage}
+"#;
+        let mut buffer = Vec::new();
+
+        let mut list = List::new(ListKind::Itemize);
+        list.push("First").push("Second");
+
+        let mut minipage = Environment::new("minipage");
+        minipage
+            .optional("t")
+            .argument(Length::text_width(0.5))
+            .push("Some text")
+            .push(list);
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_element(&minipage.into()).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn render_spacing() {
+        let should_be = r#"\vspace{1.5em}
+\bigskip
+Name:\hfill{}Date~\hspace{\dimexpr 0.5\linewidth - 1cm\relax}\linebreak{}
+\vfill
+\newpage
+"#;
+        let mut buffer = Vec::new();
+
+        let mut para = Paragraph::new();
+        para.push("Name:")
+            .push(HFill)
+            .push("Date")
+            .push(NonBreakingSpace)
+            .push(HSpace(Length::line_width(0.5) - Length::cm(1.0)))
+            .push(LineBreak);
+
+        let elements = vec![
+            Element::VSpace(Length::em(1.5)),
+            Element::BigSkip,
+            Element::Para(para),
+            Element::VFill,
+            Element::NewPage,
+        ];
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            for element in &elements {
+                printer.visit_element(element).unwrap();
+            }
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn environments_can_be_nested() {
+        let mut quote = Environment::new("quote");
+        quote.push("Quoted");
+        let mut center = Environment::new("center");
+        center.push(quote);
+
+        assert_eq!(
+            center.to_string(),
+            "\\begin{center}\n\\begin{quote}\nQuoted\n\\end{quote}\n\\end{center}\n"
+        );
+    }
+
+    #[test]
+    fn environment_options_containing_brackets_are_braced() {
+        let mut exercise = Environment::new("exercise");
+        exercise.optional("see [3]").push("Prove it.");
+
+        assert_eq!(
+            exercise.to_string(),
+            "\\begin{exercise}[{see [3]}]\nProve it.\n\\end{exercise}\n"
+        );
+    }
+
+    #[test]
+    fn preamble_with_theorems() {
+        let should_be = r#"\usepackage{amsthm}
+\newtheorem{theorem}{Theorem}[section]
+\newtheorem{lemma}[theorem]{Lemma}
+\theoremstyle{definition}
+\newtheorem{definition}{Definition}
+\theoremstyle{remark}
+\newtheorem*{remark}{Remark}
+"#;
+        let mut buffer = Vec::new();
+
+        let mut theorem = TheoremDefinition::new("theorem", "Theorem");
+        theorem.number_within("section");
+        let mut lemma = TheoremDefinition::new("lemma", "Lemma");
+        lemma.shared_with("theorem");
+        let mut definition = TheoremDefinition::new("definition", "Definition");
+        definition.style(TheoremStyle::Definition);
+        let mut remark = TheoremDefinition::new("remark", "Remark");
+        remark.style(TheoremStyle::Remark).unnumbered();
+
+        let mut preamble = Preamble::default();
+        preamble
+            .new_theorem(theorem)
+            .new_theorem(lemma)
+            .new_theorem(definition)
+            .new_theorem(remark);
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_preamble(&preamble).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn amsthm_is_loaded_after_amsmath() {
+        let theorem = TheoremDefinition::new("theorem", "Theorem");
+
+        // amsmath is needed after amsthm was already imported
+        let mut late = Preamble::default();
+        late.use_package("graphicx").new_theorem(theorem.clone());
+        late.declare_math_operator("Tr", "Tr");
+
+        // amsmath was imported first, but not straight before the theorem
+        let mut early = Preamble::default();
+        early
+            .use_package("amsmath")
+            .use_package("graphicx")
+            .new_theorem(theorem);
+
+        let inputs = vec![
+            (
+                late,
+                r"\usepackage{graphicx}
+\usepackage{amsmath}
+\usepackage{amsthm}
+\newtheorem{theorem}{Theorem}
+\DeclareMathOperator{\Tr}{Tr}
+",
+            ),
+            (
+                early,
+                r"\usepackage{amsmath}
+\usepackage{amsthm}
+\usepackage{graphicx}
+\newtheorem{theorem}{Theorem}
+",
+            ),
+        ];
+
+        for (preamble, should_be) in inputs {
+            let mut buffer = Vec::new();
+            {
+                let mut printer = Printer::new(&mut buffer);
+                printer.visit_preamble(&preamble).unwrap();
+            }
+
+            assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+        }
+    }
+
+    #[test]
+    fn theorem_cant_share_a_counter_and_be_numbered_within() {
+        let mut theorem = TheoremDefinition::new("lemma", "Lemma");
+        theorem.shared_with("theorem").number_within("section");
+        let mut preamble = Preamble::default();
+        preamble.new_theorem(theorem);
+
+        let mut printer = Printer::new(Vec::new());
+        assert!(printer.visit_preamble(&preamble).is_err());
+    }
+
+    #[test]
+    fn unnumbered_theorem_cant_use_a_counter() {
+        let mut theorem = TheoremDefinition::new("remark", "Remark");
+        theorem.unnumbered().number_within("section");
+        let mut preamble = Preamble::default();
+        preamble.new_theorem(theorem);
+
+        let mut buffer = Vec::new();
+        {
+            let mut printer = Printer::new(&mut buffer);
+            assert!(printer.visit_preamble(&preamble).is_err());
+        }
+
+        assert!(!String::from_utf8(buffer).unwrap().contains("newtheorem"));
+    }
+
+    #[test]
+    fn render_theorem_with_title_and_label() {
+        let should_be = r#"\begin{theorem}[Pythagoras]\label{thm:pythagoras}
+For a right-angled triangle,
+
+\begin{align}
+a^2 + b^2 &= c^2
+\end{align}
+\end{theorem}
+"#;
+        let mut buffer = Vec::new();
+
+        let mut theorem = Theorem::new("theorem");
+        theorem
+            .title("Pythagoras")
+            .label("thm:pythagoras")
+            .push("For a right-angled triangle,")
+            .push(Align::from("a^2 + b^2 &= c^2"));
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_theorem(&theorem).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn proof_ending_with_math_gets_qedhere() {
+        let should_be = r#"\begin{proof}[Proof of Theorem 1]
+Expanding gives
+
+\begin{align}
+(a + b)^2 &= a^2 + 2ab + b^2 \\
+&\geq 2ab \qedhere
+\end{align}
+\end{proof}
+"#;
+        let mut buffer = Vec::new();
+
+        let mut align = Align::new();
+        align
+            .push("(a + b)^2 &= a^2 + 2ab + b^2")
+            .push(r"&\geq 2ab");
+        let mut proof = Proof::new();
+        proof
+            .title("Proof of Theorem 1")
+            .push("Expanding gives")
+            .push(align);
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_proof(&proof).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn proof_ending_with_text_has_no_qedhere() {
+        let mut proof = Proof::new();
+        proof.push("Trivial.");
+
+        assert_eq!(
+            proof.to_string(),
+            "\\begin{proof}\nTrivial.\n\\end{proof}\n"
+        );
+    }
+
+    #[test]
+    fn titles_containing_brackets_are_braced() {
+        let mut theorem = Theorem::new("theorem");
+        theorem.title("Cauchy [3]").push("Statement.");
+        let mut proof = Proof::new();
+        proof.title("See [3]").push("Trivial.");
+
+        assert_eq!(
+            theorem.to_string(),
+            "\\begin{theorem}[{Cauchy [3]}]\nStatement.\n\\end{theorem}\n"
+        );
+        assert_eq!(
+            proof.to_string(),
+            "\\begin{proof}[{See [3]}]\nTrivial.\n\\end{proof}\n"
+        );
+    }
+
+    #[test]
+    fn render_empty_document() {
+        let should_be = r#"\documentclass[]{article}
+\begin{document}
+\end{document}
+"#;
+        let mut buffer = Vec::new();
+
+        let doc = Document::new(DocumentClass::Article);
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_document(&doc).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn render_empty_document_with_params() {
+        let should_be = r#"\documentclass[12pt,oneside,a4paper]{article}
+\begin{document}
+\end{document}
+"#;
+        let mut buffer = Vec::new();
+
+        let mut doc = Document::new(DocumentClass::Article);
+        doc.arguments = ["12pt", "oneside", "a4paper"]
+            .iter()
+            .map(|x| x.to_string())
+            .collect();
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_document(&doc).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn render_document_with_typed_options() {
+        let should_be = r#"\documentclass[11pt,twocolumn,fleqn,a4paper]{book}
+\begin{document}
+\end{document}
+"#;
+
+        let mut doc = Document::new(DocumentClass::Book);
+        doc.class_option(ClassOption::PointSize(PointSize::Eleven))
+            .class_option(ClassOption::TwoColumn)
+            .class_option(ClassOption::Fleqn)
+            .class_option(ClassOption::TwoColumn);
+        doc.arguments = vec![String::from("a4paper"), String::from("fleqn")];
+
+        assert_eq!(print(&doc).unwrap(), should_be);
+    }
+
+    #[test]
+    fn render_document_with_page_geometry() {
+        let should_be = r#"\documentclass[]{article}
+\usepackage{amsmath}
+\usepackage[a5paper,top=1cm,headheight=15pt]{geometry}
+\begin{document}
+\newgeometry{top=2cm,bottom=2cm,left=2cm,right=2cm}
+Wide
+\restoregeometry
+\end{document}
+"#;
+
+        let mut geometry = PageGeometry::new();
+        geometry
+            .paper(PaperSize::A5)
+            .top(Length::cm(1.0))
+            .head_height(Length::pt(15.0));
+        let mut wide = PageGeometry::new();
+        wide.margin(Length::cm(2.0)).landscape();
+
+        let mut doc = Document::new(DocumentClass::Article);
+        doc.preamble
+            .use_package("amsmath")
+            .push(PreambleElement::UsePackage {
+                package: String::from("geometry"),
+                argument: Some(String::from("margin=1in")),
+            });
+        doc.page_geometry(geometry)
+            .push(Element::NewGeometry(wide))
+            .push("Wide")
+            .push(Element::RestoreGeometry);
+
+        assert_eq!(print(&doc).unwrap(), should_be);
+    }
+
+    #[test]
+    fn render_page_styles() {
+        let should_be = r#"\documentclass[twoside]{report}
+\usepackage{fancyhdr}
+\fancypagestyle{main}{
+\fancyhf{}
+\fancyhead[RO]{\textit{Results}}
+\fancyhead[LE]{ACME}
+\fancyfoot[LO]{Confidential}
+\fancyfoot[RO]{\thepage{}}
+\renewcommand{\footrulewidth}{0.4pt}
+}
+\fancypagestyle{plain}{
+\fancyhf{}
+\fancyfoot[C]{\thepage{}}
+\renewcommand{\headrulewidth}{0pt}
+\renewcommand{\footrulewidth}{0.4pt}
+}
+\begin{document}
+\pagestyle{main}
+\thispagestyle{plain}
+\end{document}
+"#;
+
+        let mut even = HeaderFooter::new();
+        even.left("ACME");
+        let mut first = HeaderFooter::new();
+        first.center(Command::new("thepage"));
+        let mut main = PageStyle::new("main");
+        main.header.right(ParagraphElement::italic("Results"));
+        main.footer
+            .left("Confidential")
+            .right(Command::new("thepage"));
+        main.even_header(even)
+            .even_footer(HeaderFooter::new())
+            .first_footer(first)
+            .foot_rule(Length::pt(0.4));
+
+        let mut doc = Document::new(DocumentClass::Report);
+        doc.class_option(ClassOption::TwoSide);
+        doc.preamble.page_style(main);
+        doc.push(Element::PageStyle(String::from("main")))
+            .push(Element::ThisPageStyle(String::from("plain")));
+
+        assert_eq!(print(&doc).unwrap(), should_be);
+    }
+
+    #[test]
+    fn empty_headers_have_no_rule() {
+        let should_be = r#"\fancypagestyle{footer}{
+\fancyhf{}
+\fancyfoot[C]{\thepage{}}
+\renewcommand{\headrulewidth}{0pt}
+}
+"#;
+        let mut buffer = Vec::new();
+
+        let mut style = PageStyle::new("footer");
+        style.footer.center(Command::new("thepage"));
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.write_page_style(&style).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn odd_pages_are_chosen_per_row() {
+        let should_be = r#"\fancypagestyle{notes}{
+\fancyhf{}
+\fancyhead[LO]{Notes}
+\fancyhead[RE]{Draft}
+\fancyfoot[C]{\thepage{}}
+}
+"#;
+        let mut buffer = Vec::new();
+
+        let mut even = HeaderFooter::new();
+        even.right("Draft");
+        let mut style = PageStyle::new("notes");
+        style.header.left("Notes");
+        style.footer.center(Command::new("thepage"));
+        style.even_header(even);
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.write_page_style(&style).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn redefined_colours_come_after_the_ones_they_use() {
+        let should_be = r#"\usepackage{xcolor}
+\definecolor{b}{RGB}{0,0,255}
+\colorlet{a}{b!50}
+"#;
+        let mut buffer = Vec::new();
+
+        let mut preamble = Preamble::default();
+        preamble.define_color("a", Color::Rgb(255, 0, 0));
+        let b = preamble.define_color("b", Color::Rgb(0, 0, 255));
+        preamble.define_color("a", b.percent(50));
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_preamble(&preamble).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn render_colors() {
+        let should_be = r#"\documentclass[]{article}
+\usepackage{xcolor}
+\definecolor{brand}{HTML}{1F6FEB}
+\definecolor{warn}{RGB}{250,200,0}
+\colorlet{faded}{brand!30!white}
+\begin{document}
+\textcolor{brand}{Title} \colorbox{faded}{\textbf{Note}} \fcolorbox{red}{red!10}{Stop} \fcolorbox[RGB]{0,0,0}{250,200,0}{Caution}
+\end{document}
+"#;
+
+        let mut doc = Document::new(DocumentClass::Article);
+        doc.preamble.define_color("brand", Color::Rgb(0, 0, 0));
+        let warn = doc.preamble.define_color("warn", Color::Rgb(250, 200, 0));
+        let brand = doc.preamble.define_color("brand", Color::Html(0x1F6FEB));
+        let faded = doc
+            .preamble
+            .define_color("faded", brand.clone().mix(30, Color::named("white")));
+        doc.preamble
+            .define_color("faded", brand.clone().mix(30, "white".into()));
+
+        let mut para = Paragraph::new();
+        para.push(ParagraphElement::colored(brand, "Title"))
+            .push(" ")
+            .push(ParagraphElement::color_box(
+                faded,
+                ParagraphElement::bold("Note"),
+            ))
+            .push(" ")
+            .push(ParagraphElement::framed_color_box(
+                "red",
+                Color::named("red").percent(10),
+                "Stop",
+            ))
+            .push(" ")
+            .push(ParagraphElement::framed_color_box(
+                Color::Rgb(0, 0, 0),
+                Color::Rgb(250, 200, 0),
+                "Caution",
+            ));
+        doc.push(para);
+
+        assert_eq!(print(&doc).unwrap(), should_be);
+
+        let mut doc = Document::new(DocumentClass::Article);
+        doc.push(Paragraph::from(ParagraphElement::framed_color_box(
+            warn,
+            Color::Html(0xFFFFFF),
+            "Mixed models",
+        )));
+        assert!(print(&doc).is_err());
+    }
+
+    #[test]
+    fn conflicting_options_cant_be_printed() {
+        let mut doc = Document::new(DocumentClass::Article);
+        doc.class_option(ClassOption::Paper(PaperSize::A4));
+        doc.arguments = vec![String::from("letterpaper")];
 
-\title{Sample Document}
-"#;
+        assert!(print(&doc).is_err());
+    }
+
+    #[test]
+    fn render_enumerated_list() {
+        let should_be = "\\begin{enumerate}\n\\end{enumerate}\n";
         let mut buffer = Vec::new();
 
-        let mut preamble = Preamble::default();
-        preamble
-            .title("Sample Document")
-            .use_package("amsmath")
-            .use_package("graphics");
+        let list = List::new(ListKind::Enumerate);
 
         {
             let mut printer = Printer::new(&mut buffer);
-            printer.visit_preamble(&preamble).unwrap();
+            printer.visit_list(&list).unwrap();
         }
 
         assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
     }
 
     #[test]
-    fn preamble_with_newcommand() {
-        let should_be = r#"\newcommand{\Love}[2]{
-#1 loves #2
-}
-"#;
+    fn render_empty_itemize_list() {
+        let should_be = "\\begin{itemize}\n\\end{itemize}\n";
         let mut buffer = Vec::new();
-        let mut preamble = Preamble::default();
-        preamble.new_command("Love", 2, "#1 loves #2");
+
+        let list = List::new(ListKind::Itemize);
 
         {
             let mut printer = Printer::new(&mut buffer);
-            printer.visit_preamble(&preamble).unwrap();
+            printer.visit_list(&list).unwrap();
         }
 
         assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
     }
 
     #[test]
-    fn preamble_with_newcommand_with_default_argument() {
-        let should_be = r#"\newcommand{\Love}[3][likes]{
-#2 #1 #3
-}
-"#;
+    fn render_list_with_items() {
+        let should_be = r"\begin{itemize}
+\item This
+\item is
+\item a
+\item list!
+\end{itemize}
+";
         let mut buffer = Vec::new();
-        let mut preamble = Preamble::default();
-        preamble.push(PreambleElement::NewCommand {
-            name: String::from("Love"),
-            args_num: Some(3),
-            default_arg: Some(String::from("likes")),
-            definition: String::from("#2 #1 #3"),
-        });
+
+        let mut list = List::new(ListKind::Itemize);
+        list.push("This").push("is").push("a").push("list!");
 
         {
             let mut printer = Printer::new(&mut buffer);
-            printer.visit_preamble(&preamble).unwrap();
+            printer.visit_list(&list).unwrap();
         }
 
         assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
     }
 
     #[test]
-    fn render_empty_document() {
-        let should_be = r#"\documentclass[]{article}
-\begin{document}
-\end{document}
-"#;
+    fn render_nested_lists() {
+        let should_be = r"\begin{itemize}
+\item \textbf{Fruit}
+  \begin{enumerate}
+  \item Apples
+  \item Pears
+    \begin{itemize}
+    \item Nashi
+    \end{itemize}
+  \end{enumerate}
+\item Vegetables
+
+Some more text.
+\end{itemize}
+";
         let mut buffer = Vec::new();
 
-        let doc = Document::new(DocumentClass::Article);
+        let mut pears = Item::from("Pears");
+        let mut varieties = List::new(ListKind::Itemize);
+        varieties.push("Nashi");
+        pears.push(varieties);
 
-        {
-            let mut printer = Printer::new(&mut buffer);
-            printer.visit_document(&doc).unwrap();
-        }
+        let mut fruit = List::new(ListKind::Enumerate);
+        fruit.push("Apples").push(pears);
 
-        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
-    }
+        let mut first = Item::new(ParagraphElement::bold("Fruit"));
+        first.push(fruit);
+        let mut second = Item::from("Vegetables");
+        second.push("Some more text.");
 
-    #[test]
-    fn render_empty_document_with_params() {
-        let should_be = r#"\documentclass[12pt,oneside,a4paper]{article}
-\begin{document}
-\end{document}
-"#;
-        let mut buffer = Vec::new();
+        let mut list = List::new(ListKind::Itemize);
+        list.push(first).push(second);
 
-        let mut doc = Document::new(DocumentClass::Article);
-        doc.arguments = ["12pt", "oneside", "a4paper"]
-            .iter()
-            .map(|x| x.to_string())
-            .collect();
         {
             let mut printer = Printer::new(&mut buffer);
-            printer.visit_document(&doc).unwrap();
+            printer.visit_list(&list).unwrap();
         }
 
         assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
     }
 
     #[test]
-    fn render_enumerated_list() {
-        let should_be = "\\begin{enumerate}\n\\end{enumerate}\n";
+    fn render_description_list() {
+        let should_be = r"\begin{description}
+\item[\textbf{AST}] Abstract Syntax Tree
+\item[LaTeX] A document preparation system
+\end{description}
+";
         let mut buffer = Vec::new();
 
-        let list = List::new(ListKind::Enumerate);
+        let mut list = List::new(ListKind::Description);
+        list.push(Item::labelled(
+            ParagraphElement::bold("AST"),
+            "Abstract Syntax Tree",
+        ))
+        .push(Item::labelled("LaTeX", "A document preparation system"));
 
         {
             let mut printer = Printer::new(&mut buffer);
@@ -461,11 +2319,22 @@ mod tests {
     }
 
     #[test]
-    fn render_empty_itemize_list() {
-        let should_be = "\\begin{itemize}\n\\end{itemize}\n";
+    fn render_list_with_options() {
+        let should_be = r"\begin{enumerate}[start=4, label={(\alph*]}, nosep]
+\item[$\star$] Starred
+\item[{[a, b]}] Interval
+\item Normal
+\end{enumerate}
+";
         let mut buffer = Vec::new();
 
-        let list = List::new(ListKind::Itemize);
+        let mut list = List::new(ListKind::Enumerate);
+        list.options.start = Some(4);
+        list.options.label = Some(Numbering::LowerAlpha.label("(", "]"));
+        list.options.compact = true;
+        list.push(Item::labelled(InlineMath(r"\star".to_string()), "Starred"))
+            .push(Item::labelled("[a, b]", "Interval"))
+            .push("Normal");
 
         {
             let mut printer = Printer::new(&mut buffer);
@@ -476,18 +2345,24 @@ mod tests {
     }
 
     #[test]
-    fn render_list_with_items() {
+    fn render_checklist() {
         let should_be = r"\begin{itemize}
-\item This
-\item is
-\item a
-\item list!
+\item[\ding{52}] Release notes
+\item[\ding{113}] Publish
+  \begin{itemize}
+  \item[\ding{113}] crates.io
+  \end{itemize}
 \end{itemize}
 ";
         let mut buffer = Vec::new();
 
-        let mut list = List::new(ListKind::Itemize);
-        list.push("This").push("is").push("a").push("list!");
+        let mut nested = List::new(ListKind::Checklist(CheckboxStyle::Pifont));
+        nested.push("crates.io");
+        let mut publish = Item::task(false, "Publish");
+        publish.push(nested);
+
+        let mut list = List::new(ListKind::Checklist(CheckboxStyle::Pifont));
+        list.push(Item::task(true, "Release notes")).push(publish);
 
         {
             let mut printer = Printer::new(&mut buffer);
@@ -497,6 +2372,20 @@ mod tests {
         assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
     }
 
+    #[test]
+    fn require_package_only_adds_it_once() {
+        let mut preamble = Preamble::default();
+        preamble
+            .use_package("enumitem")
+            .require_package("enumitem")
+            .require_package("amsmath")
+            .require_package("amsmath");
+
+        assert_eq!(preamble.iter().count(), 2);
+        assert!(preamble.has_package("amsmath"));
+        assert!(!preamble.has_package("graphicx"));
+    }
+
     #[test]
     fn render_blank_section() {
         let should_be = "\\section{First Section}\n";
@@ -557,6 +2446,47 @@ mod tests {
         assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
     }
 
+    #[test]
+    fn render_every_sectioning_level() {
+        let should_be = r"\part{A}
+\chapter{B}
+\section{C}
+\subsection{D}
+\subsubsection{E}
+\paragraph{F}
+\subparagraph{G}
+";
+        let mut buffer = Vec::new();
+
+        let elements: Vec<Element> = vec![
+            Part::new("A").into(),
+            Chapter::new("B").into(),
+            Section::new("C").into(),
+            Subsection::new("D").into(),
+            Subsubsection::new("E").into(),
+            ParagraphSection::new("F").into(),
+            Subparagraph::new("G").into(),
+        ];
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            for element in &elements {
+                printer.visit_element(element).unwrap();
+            }
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn sectioning_levels() {
+        assert_eq!(Part::new("").level(), -1);
+        assert_eq!(Chapter::new("").level(), 0);
+        assert_eq!(Section::new("").level(), 1);
+        assert_eq!(Subsubsection::new("").level(), 3);
+        assert_eq!(Subparagraph::new("").level(), 5);
+    }
+
     #[test]
     fn section_with_paragraphs() {
         let should_be = r#"\section{First Section}
@@ -602,6 +2532,83 @@ Hello World!
         assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
     }
 
+    #[test]
+    fn section_with_short_name_and_label() {
+        let should_be = "\\section[Intro]{A Rather Long Introduction}\\label{sec:intro}\n";
+        let mut buffer = Vec::new();
+
+        let mut section = Section::new("A Rather Long Introduction");
+        section.short_name("Intro").label("sec:intro");
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_sectioning_element(&section).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn short_name_containing_a_bracket() {
+        let should_be = "\\section[{Intervals [a, b]}]{Closed Intervals $[a, b]$}\n";
+        let mut buffer = Vec::new();
+
+        let mut section = Section::new_formatted(ParagraphElement::span(vec![
+            ParagraphElement::from("Closed Intervals "),
+            InlineMath(String::from("[a, b]")),
+        ]));
+        section.short_name("Intervals [a, b]");
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_sectioning_element(&section).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn unnumbered_section_in_table_of_contents() {
+        let should_be = r"\chapter*{Preface to the Second Edition}
+\addcontentsline{toc}{chapter}{Preface}
+";
+        let mut buffer = Vec::new();
+
+        let mut chapter = Chapter::new("Preface to the Second Edition");
+        chapter.short_name("Preface");
+        chapter.numbered = false;
+        chapter.in_toc = true;
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_sectioning_element(&chapter).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn render_document_with_toc_and_secnum_depth() {
+        let should_be = r#"\documentclass[]{report}
+\setcounter{tocdepth}{1}
+\setcounter{secnumdepth}{2}
+\begin{document}
+\end{document}
+"#;
+        let mut buffer = Vec::new();
+
+        let mut doc = Document::new(DocumentClass::Report);
+        doc.toc_depth = Some(1);
+        doc.secnum_depth = Some(2);
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_document(&doc).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
     #[test]
     fn section_with_link() {
         let should_be = r#"\section{\href{some_link}{https:\\example.com}}
@@ -644,7 +2651,7 @@ Hello World!
 
     #[test]
     fn render_simple_equation() {
-        let should_be = "x &= y + \\sigma \\\\\n";
+        let should_be = "x &= y + \\sigma\n";
         let mut buffer = Vec::new();
         let eq = Equation::new(r"x &= y + \sigma");
 
@@ -660,7 +2667,7 @@ Hello World!
     fn render_several_equations() {
         let should_be = r"\begin{align}
 E &= m c^2 \label{eq:mass-energy-equivalence} \\
-y &= m x + c \\
+y &= m x + c
 \end{align}
 ";
         let mut buffer = Vec::new();
@@ -684,7 +2691,7 @@ y &= m x + c \\
 
     #[test]
     fn equation_with_label() {
-        let should_be = "E &= m c^2 \\label{eq:mass-energy-equivalence} \\\\\n";
+        let should_be = "E &= m c^2 \\label{eq:mass-energy-equivalence}\n";
         let mut buffer = Vec::new();
 
         let mut eq = Equation::new("E &= m c^2");
@@ -700,7 +2707,7 @@ y &= m x + c \\
 
     #[test]
     fn equation_with_no_numbering() {
-        let should_be = "E &= m c^2 \\nonumber \\\\\n";
+        let should_be = "E &= m c^2 \\nonumber\n";
         let mut buffer = Vec::new();
 
         let mut eq = Equation::new("E &= m c^2");
@@ -714,6 +2721,158 @@ y &= m x + c \\
         assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
     }
 
+    #[test]
+    fn render_gather_with_tags() {
+        let should_be = r"\begin{gather}
+a = b \tag{$\ast$} \\
+c = d \label{eq:cd} \\
+e = f \nonumber
+\end{gather}
+";
+        let mut buffer = Vec::new();
+
+        let mut e = Equation::new("e = f");
+        e.not_numbered();
+        let mut a = Equation::new("a = b");
+        a.tag(r"$\ast$");
+        let mut math = DisplayMath::new(MathEnvironment::Gather);
+        math.push(a)
+            .push(Equation::with_label("eq:cd", "c = d"))
+            .push(e);
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_display_math(&math).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn render_unnumbered_alignat() {
+        let should_be = r"\begin{alignat*}{2}
+x &= 1 &\quad y &= 2 \\
+z &= 3 &\quad w &= 4
+\end{alignat*}
+";
+        let mut buffer = Vec::new();
+
+        let mut last = Equation::new(r"z &= 3 &\quad w &= 4");
+        last.not_numbered();
+        let mut math = DisplayMath::unnumbered(MathEnvironment::Alignat(2));
+        math.push(r"x &= 1 &\quad y &= 2").push(last);
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_display_math(&math).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn label_without_a_number_is_still_written() {
+        let should_be = "\\begin{align*}\nx &= y \\label{eq:oops}\n\\end{align*}\n";
+        let mut buffer = Vec::new();
+        let mut math = DisplayMath::unnumbered(MathEnvironment::Align);
+        math.push(Equation::with_label("eq:oops", "x &= y"));
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_display_math(&math).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn equation_environment_only_allows_one_line() {
+        let mut buffer = Vec::new();
+        let mut math = DisplayMath::new(MathEnvironment::Equation);
+        math.push("x = 1").push("y = 2");
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            assert!(printer.visit_display_math(&math).is_err());
+        }
+
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn alignat_needs_a_column() {
+        let mut buffer = Vec::new();
+        let mut math = DisplayMath::new(MathEnvironment::Alignat(0));
+        math.push("x &= 1");
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            assert!(printer.visit_display_math(&math).is_err());
+        }
+
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn multline_gets_a_single_label() {
+        let should_be = r"\begin{multline}
+a + b + c \\
++ d + e \label{eq:long}
+\end{multline}
+";
+        let mut buffer = Vec::new();
+
+        let mut math = DisplayMath::new(MathEnvironment::Multline);
+        math.push(Equation::with_label("eq:long", "a + b + c"))
+            .push("+ d + e");
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_display_math(&math).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn render_subequations_with_nested_split() {
+        let should_be = r"\begin{subequations}
+\label{eq:group}
+\begin{equation}
+\begin{split}
+a &= b \\
+&= c
+\end{split} \label{eq:split}
+\end{equation}
+\end{subequations}
+";
+        let mut buffer = Vec::new();
+
+        let mut split = Split::new();
+        split.push("a &= b").push("&= c");
+        let mut eq = Equation::from(split);
+        eq.label("eq:split");
+        let mut math = DisplayMath::from(eq);
+        math.group_label("eq:group");
+
+        {
+            let mut printer = Printer::new(&mut buffer);
+            printer.visit_display_math(&math).unwrap();
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), should_be);
+    }
+
+    #[test]
+    fn render_cases() {
+        let should_be = "\\begin{cases}\nx & x \\geq 0 \\\\\n-x & x < 0\n\\end{cases}";
+
+        let mut cases = Cases::new();
+        cases.push("x", r"x \geq 0").push("-x", "x < 0");
+
+        assert_eq!(cases.to_string(), should_be);
+    }
+
     #[test]
     fn partial_document() {
         let should_be = "";
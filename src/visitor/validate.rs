@@ -0,0 +1,1057 @@
+use std::fmt::{self, Display, Formatter};
+
+use super::{Printer, Visitor};
+use code::{CodeBackend, CodeBlock};
+use color::Color;
+use document::{Document, DocumentClass, Element, PreambleElement};
+use environment::Environment;
+use equations::{Align, DisplayMath, Equation, MathEnvironment};
+use failure::Error;
+use lists::{Item, List, ListKind};
+use options::ClassOption;
+use page_style::is_builtin_page_style;
+use paragraph::{Paragraph, ParagraphElement};
+use section::SectionElement;
+use theorem::{Proof, Theorem};
+
+/// Check a document's structure against its `DocumentClass`.
+pub fn validate(doc: &Document) -> Result<Vec<Diagnostic>, Error> {
+    let mut validator = Validator::new();
+    validator.visit_document(doc)?;
+    Ok(validator.into_diagnostics())
+}
+
+/// How serious a `Diagnostic` is.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    /// The document will compile, but probably not the way you wanted.
+    Warning,
+    /// The document won't compile.
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match *self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A problem found while validating a document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    /// How serious the problem is.
+    pub severity: Severity,
+    /// Where the problem is, as a list of nodes starting from the top of the
+    /// document (e.g. `["[0] \chapter{Intro}", "[2] list", "[1] item"]`).
+    /// This is empty for problems with the document as a whole.
+    pub path: Vec<String>,
+    /// What is wrong.
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.severity, self.message)?;
+        if !self.path.is_empty() {
+            write!(f, " (at {})", self.path.join(" > "))?;
+        }
+        Ok(())
+    }
+}
+
+/// A `Visitor` which checks a document's structure, looking for things like
+/// sectioning levels the `DocumentClass` doesn't have, skipped heading
+/// levels, and packages which are used but never imported.
+///
+/// # Examples
+///
+/// ```rust
+/// use latex::{Chapter, Document, DocumentClass, Element, Severity, Validator, Visitor};
+///
+/// let mut doc = Document::new(DocumentClass::Article);
+/// doc.push(Element::TitlePage).push(Chapter::new("Introduction"));
+///
+/// let mut validator = Validator::new();
+/// validator.visit_document(&doc).unwrap();
+/// let diagnostics = validator.into_diagnostics();
+///
+/// assert_eq!(diagnostics.len(), 2);
+/// assert!(diagnostics.iter().all(|d| d.severity == Severity::Error));
+/// assert_eq!(
+///     diagnostics[1].to_string(),
+///     r"error: \chapter isn't available in the article class (at [1] \chapter{Introduction})"
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Validator {
+    class: DocumentClass,
+    review: bool,
+    has_title: bool,
+    has_author: bool,
+    has_chapters: bool,
+    code_backend: CodeBackend,
+    path: Vec<String>,
+    levels: Vec<i8>,
+    /// Each package which is needed, where it was first needed, and why.
+    required: Vec<(String, Vec<String>, String)>,
+    theorems: Vec<String>,
+    math_operators: Vec<String>,
+    page_styles: Vec<String>,
+    colors: Vec<String>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Validator {
+    /// Create a new `Validator`.
+    pub fn new() -> Validator {
+        Default::default()
+    }
+
+    /// The problems found so far, in the last document visited.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Consume the validator, returning all the problems it found.
+    pub fn into_diagnostics(self) -> Vec<Diagnostic> {
+        self.diagnostics
+    }
+
+    fn report(&mut self, severity: Severity, message: String) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            path: self.path.clone(),
+            message,
+        });
+    }
+
+    /// Warn about labels on lines which don't get a number, since `\ref`
+    /// would point at whatever was numbered last.
+    fn check_equation_labels<'a, I>(&mut self, equations: I, numbered: bool)
+    where
+        I: IntoIterator<Item = &'a Equation>,
+    {
+        for equation in equations {
+            if let Some(label) = equation.get_label() {
+                if !(numbered && equation.is_numbered()) && equation.get_tag().is_none() {
+                    self.report(
+                        Severity::Warning,
+                        format!("The label \"{}\" is on an equation without a number", label),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Remember that a package is needed, unless we already know.
+    fn require(&mut self, package: &str, reason: &str) {
+        if !self.required.iter().any(|(p, _, _)| p == package) {
+            self.required
+                .push((package.to_string(), self.path.clone(), reason.to_string()));
+        }
+    }
+
+    /// Visit an element inside a container, keeping track of where it is.
+    fn visit_child(&mut self, index: usize, element: &Element) -> Result<(), Error> {
+        self.path.push(format!("[{}] {}", index, describe(element)));
+
+        match *element {
+            Element::TitlePage if self.class != DocumentClass::Part => {
+                if !self.has_title {
+                    self.report(
+                        Severity::Error,
+                        String::from(
+                            r"\maketitle needs a title, but the preamble doesn't have one",
+                        ),
+                    );
+                } else if !self.has_author {
+                    self.report(
+                        Severity::Warning,
+                        String::from(
+                            r"\maketitle is used, but the preamble doesn't have an author",
+                        ),
+                    );
+                }
+            }
+            Element::Command(ref command) => {
+                for arg in &command.arguments {
+                    self.check_paragraph_element(arg.value());
+                }
+            }
+            Element::NewGeometry(ref geometry) => {
+                self.require("geometry", r"\newgeometry");
+                if geometry.sets_paper() {
+                    self.report(
+                        Severity::Warning,
+                        String::from(
+                            r"\newgeometry can't change the paper size or orientation, so they are ignored",
+                        ),
+                    );
+                }
+            }
+            Element::RestoreGeometry => self.require("geometry", r"\restoregeometry"),
+            Element::PageStyle(ref name) | Element::ThisPageStyle(ref name)
+                if !is_builtin_page_style(name) && !self.page_styles.contains(name) =>
+            {
+                self.report(
+                    Severity::Error,
+                    format!("The \"{}\" page style was never defined", name),
+                );
+            }
+            _ => {}
+        }
+
+        let result = self.visit_element(element);
+        self.path.pop();
+        result
+    }
+
+    fn visit_children<'a, I>(&mut self, elements: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = &'a Element>,
+    {
+        for (i, element) in elements.into_iter().enumerate() {
+            self.visit_child(i, element)?;
+        }
+        Ok(())
+    }
+
+    /// The highest heading level which can appear at the top of the document
+    /// (ignoring `\part`), if the class is one we know about.
+    fn base_level(&self) -> Option<i8> {
+        match self.class {
+            DocumentClass::Article => Some(1),
+            DocumentClass::Book | DocumentClass::Report => Some(0),
+            DocumentClass::Part | DocumentClass::Other(_) => None,
+        }
+    }
+
+    fn check_heading_level<T: SectionElement>(&mut self, section: &T) {
+        let level = section.level();
+        let command = section.get_section_name();
+
+        if level == 0 && self.class == DocumentClass::Article {
+            self.report(
+                Severity::Error,
+                format!(r"\{} isn't available in the {} class", command, self.class),
+            );
+            return;
+        }
+
+        let expected = match self.levels.last() {
+            Some(&parent) if level <= parent => {
+                self.report(
+                    Severity::Warning,
+                    format!(
+                        r"\{} is nested inside a heading at the same or a lower level",
+                        command
+                    ),
+                );
+                return;
+            }
+            None | Some(&-1) => self.base_level(),
+            Some(&parent) => Some(parent + 1),
+        };
+
+        if let Some(expected) = expected {
+            if level > expected {
+                self.report(
+                    Severity::Warning,
+                    format!(r"\{} skips {} heading level(s)", command, level - expected),
+                );
+            }
+        }
+    }
+
+    fn check_paragraph_element(&mut self, element: &ParagraphElement) {
+        match *element {
+            ParagraphElement::Link(_) => self.require("hyperref", r"\href"),
+            ParagraphElement::StrikeThrough(_) => self.require("ulem", r"\sout"),
+            ParagraphElement::Todo(_) if self.review => self.require("todonotes", r"\todo"),
+            ParagraphElement::InlineCode(_) => self.require_code_backend(),
+            ParagraphElement::Colored(ref color, _) => {
+                self.require("xcolor", r"\textcolor");
+                self.check_color(color);
+            }
+            ParagraphElement::ColorBox(ref color, _) => {
+                self.require("xcolor", r"\colorbox");
+                self.check_color(color);
+            }
+            ParagraphElement::FramedColorBox(ref frame, ref background, _) => {
+                self.require("xcolor", r"\fcolorbox");
+                self.check_color(frame);
+                self.check_color(background);
+            }
+            _ => {}
+        }
+
+        match *element {
+            ParagraphElement::Bold(ref e)
+            | ParagraphElement::Italic(ref e)
+            | ParagraphElement::Emph(ref e)
+            | ParagraphElement::Underline(ref e)
+            | ParagraphElement::Monospace(ref e)
+            | ParagraphElement::SmallCaps(ref e)
+            | ParagraphElement::StrikeThrough(ref e)
+            | ParagraphElement::Superscript(ref e)
+            | ParagraphElement::Subscript(ref e)
+            | ParagraphElement::FontSize(_, ref e)
+            | ParagraphElement::Footnote(ref e)
+            | ParagraphElement::FootnoteText(ref e)
+            | ParagraphElement::MarginNote(ref e)
+            | ParagraphElement::Todo(ref e)
+            | ParagraphElement::Colored(_, ref e)
+            | ParagraphElement::ColorBox(_, ref e)
+            | ParagraphElement::FramedColorBox(_, _, ref e) => self.check_paragraph_element(e),
+            ParagraphElement::Span(ref elements) => {
+                for e in elements {
+                    self.check_paragraph_element(e);
+                }
+            }
+            ParagraphElement::Command(ref command) => {
+                if !command.has_valid_name() {
+                    self.report(
+                        Severity::Error,
+                        format!("\"{}\" isn't a valid command name", command.name),
+                    );
+                }
+                if self.math_operators.contains(&command.name) {
+                    self.report(
+                        Severity::Error,
+                        format!(
+                            r"\{} is a math operator, so it can only be used in maths",
+                            command.name
+                        ),
+                    );
+                }
+                for arg in &command.arguments {
+                    self.check_paragraph_element(arg.value());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Make sure a colour can be written and only uses names which exist.
+    fn check_color(&mut self, color: &Color) {
+        if let Err(e) = color.spec() {
+            self.report(Severity::Error, e.to_string());
+        }
+
+        let undefined: Vec<String> = color
+            .names()
+            .into_iter()
+            .filter(|name| !Color::is_base_color(name) && !self.colors.iter().any(|c| c == name))
+            .map(String::from)
+            .collect();
+        for name in undefined {
+            self.report(
+                Severity::Error,
+                format!("The \"{}\" colour was never defined", name),
+            );
+        }
+    }
+
+    /// Options which are already the class's default don't do anything.
+    fn check_class_options(&mut self, doc: &Document, options: &[ClassOption]) {
+        for option in options {
+            if option.is_default_for(&doc.class) {
+                self.report(
+                    Severity::Warning,
+                    format!(
+                        "The \"{}\" option is already the default for the {} class",
+                        option, doc.class
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Odd and even headers only make sense in a two-sided document.
+    fn check_page_sides(&mut self, doc: &Document, options: &[ClassOption]) {
+        let two_sided = match doc.class {
+            DocumentClass::Book => !options.contains(&ClassOption::OneSide),
+            DocumentClass::Other(_) => true,
+            _ => options.contains(&ClassOption::TwoSide),
+        };
+        if two_sided {
+            return;
+        }
+
+        for item in doc.preamble.iter() {
+            if let PreambleElement::PageStyle(ref style) = *item {
+                if style.has_even_pages() {
+                    self.report(
+                        Severity::Warning,
+                        format!(
+                            "The \"{}\" page style has separate even pages, but the document is one-sided",
+                            style.name
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    /// A style with a first page header or footer also defines `plain`, so
+    /// only one style can do that.
+    fn check_first_pages(&mut self, doc: &Document) {
+        let plain_styles = doc
+            .preamble
+            .iter()
+            .filter(|item| match **item {
+                PreambleElement::PageStyle(ref style) => {
+                    style.name == "plain" || style.has_first_pages()
+                }
+                _ => false,
+            })
+            .count();
+
+        if plain_styles > 1 {
+            self.report(
+                Severity::Warning,
+                String::from(
+                    "The \"plain\" page style is defined more than once, only the last one is used",
+                ),
+            );
+        }
+    }
+
+    fn require_code_backend(&mut self) {
+        let backend = self.code_backend;
+        for package in backend.packages() {
+            self.require(package, "the code backend");
+        }
+    }
+}
+
+/// A short description of an element, for use in a `Diagnostic`'s path.
+fn describe(element: &Element) -> String {
+    fn heading<T: SectionElement>(section: &T) -> String {
+        let mut name = Vec::new();
+        let printed = Printer::new(&mut name).visit_paragraph_element(section.get_name());
+
+        // an unprintable name is reported on its own, so just leave it out
+        match (printed, String::from_utf8(name)) {
+            (Ok(()), Ok(name)) => format!(r"\{}{{{}}}", section.get_section_name(), name),
+            _ => format!(r"\{}{{...}}", section.get_section_name()),
+        }
+    }
+
+    match *element {
+        Element::Part(ref s) => heading(s),
+        Element::Chapter(ref s) => heading(s),
+        Element::Section(ref s) => heading(s),
+        Element::Subsection(ref s) => heading(s),
+        Element::Subsubsection(ref s) => heading(s),
+        Element::ParagraphSection(ref s) => heading(s),
+        Element::Subparagraph(ref s) => heading(s),
+        Element::Para(_) => String::from("paragraph"),
+        Element::TitlePage => String::from(r"\maketitle"),
+        Element::Align(_) => String::from("align"),
+        Element::DisplayMath(ref math) => math.environment.environment_name().to_string(),
+        Element::Environment(ref name, _) => name.clone(),
+        Element::Block(ref env) => env.name.clone(),
+        Element::List(_) => String::from("list"),
+        Element::CodeBlock(_) => String::from("code block"),
+        Element::Command(ref command) => format!(r"\{}", command.name),
+        Element::Theorem(ref theorem) => theorem.environment.clone(),
+        Element::Proof(_) => String::from("proof"),
+        Element::NewGeometry(_) => String::from(r"\newgeometry"),
+        Element::RestoreGeometry => String::from(r"\restoregeometry"),
+        Element::PageStyle(_) => String::from(r"\pagestyle"),
+        Element::ThisPageStyle(_) => String::from(r"\thispagestyle"),
+        _ => String::from("element"),
+    }
+}
+
+impl Visitor for Validator {
+    fn visit_document(&mut self, doc: &Document) -> Result<(), Error> {
+        // nothing carries over from the last document
+        *self = Validator::default();
+        self.class = doc.class.clone();
+        self.has_title = doc.preamble.title.is_some();
+        self.has_author = doc.preamble.author.is_some();
+        self.review = doc.review;
+        self.code_backend = doc.code_backend;
+        self.theorems = doc
+            .preamble
+            .iter()
+            .filter_map(|item| match *item {
+                PreambleElement::NewTheorem(ref theorem) => Some(theorem.name.clone()),
+                _ => None,
+            })
+            .collect();
+
+        self.math_operators = doc
+            .preamble
+            .iter()
+            .filter_map(|item| match *item {
+                PreambleElement::DeclareMathOperator { ref name, .. } => Some(name.clone()),
+                _ => None,
+            })
+            .collect();
+
+        self.page_styles = doc
+            .preamble
+            .iter()
+            .filter_map(|item| match *item {
+                PreambleElement::PageStyle(ref style) => Some(style.name.clone()),
+                _ => None,
+            })
+            .collect();
+        if doc.preamble.has_package("fancyhdr") {
+            self.page_styles.push(String::from("fancy"));
+        }
+
+        // a colour can only refer to the ones defined before it
+        self.colors.clear();
+        for item in doc.preamble.iter() {
+            match *item {
+                PreambleElement::DefineColor {
+                    ref name,
+                    ref color,
+                } => {
+                    self.check_color(color);
+                    self.colors.push(name.clone());
+                }
+                PreambleElement::PageStyle(ref style) => {
+                    let rows = [
+                        Some(&style.header),
+                        Some(&style.footer),
+                        style.even_header.as_ref(),
+                        style.even_footer.as_ref(),
+                        style.first_header.as_ref(),
+                        style.first_footer.as_ref(),
+                    ];
+                    for row in rows.iter().flatten() {
+                        for (_, content) in row.parts() {
+                            self.check_paragraph_element(content);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut options = Vec::new();
+        if doc.class == DocumentClass::Part {
+            if !doc.preamble.is_empty()
+                || doc.preamble.title.is_some()
+                || doc.preamble.author.is_some()
+            {
+                self.report(
+                    Severity::Warning,
+                    String::from("The preamble of a partial document is never written out"),
+                );
+            }
+        } else {
+            match doc.class_options() {
+                Ok(resolved) => options = resolved,
+                Err(e) => self.report(Severity::Error, e.to_string()),
+            }
+            self.check_class_options(doc, &options);
+            self.check_page_sides(doc, &options);
+            self.check_first_pages(doc);
+        }
+
+        self.has_chapters = false;
+        self.visit_children(doc.iter())?;
+
+        for option in &options {
+            if let ClassOption::OpenRight | ClassOption::OpenAny = *option {
+                if !self.has_chapters {
+                    self.report(
+                        Severity::Warning,
+                        format!(
+                            "The \"{}\" option only affects chapters, but there aren't any",
+                            option
+                        ),
+                    );
+                }
+            }
+        }
+
+        // a partial document gets its packages from whoever includes it
+        if doc.class != DocumentClass::Part {
+            for (package, path, reason) in self.required.clone() {
+                if !doc.preamble.has_package(&package) {
+                    self.diagnostics.push(Diagnostic {
+                        severity: Severity::Error,
+                        path,
+                        message: format!(
+                            "{} needs the {} package, but it isn't imported",
+                            reason, package
+                        ),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn visit_paragraph(&mut self, paragraph: &Paragraph) -> Result<(), Error> {
+        for element in paragraph.iter() {
+            self.check_paragraph_element(element);
+        }
+        Ok(())
+    }
+
+    fn visit_sectioning_element<T: SectionElement>(&mut self, section: &T) -> Result<(), Error> {
+        self.check_heading_level(section);
+        if section.level() == 0 {
+            self.has_chapters = true;
+        }
+        self.check_paragraph_element(section.get_name());
+        if let (Some(label), false) = (section.get_label(), section.numbered()) {
+            self.report(
+                Severity::Warning,
+                format!(
+                    "The label \"{}\" is on a \\{}* without a number",
+                    label,
+                    section.get_section_name()
+                ),
+            );
+        }
+
+        self.levels.push(section.level());
+        let result = self.visit_children(section.iter());
+        self.levels.pop();
+        result
+    }
+
+    fn visit_align(&mut self, align: &Align) -> Result<(), Error> {
+        self.require("amsmath", "the align environment");
+        self.check_equation_labels(align.iter(), true);
+        Ok(())
+    }
+
+    fn visit_display_math(&mut self, math: &DisplayMath) -> Result<(), Error> {
+        let plain_equation = math.environment == MathEnvironment::Equation
+            && math.numbered
+            && !math.is_grouped()
+            && math.iter().all(|eq| eq.get_tag().is_none());
+
+        if !plain_equation {
+            let reason = format!("the {} environment", math.environment.environment_name());
+            self.require("amsmath", &reason);
+        }
+
+        if math.environment == MathEnvironment::Multline {
+            // the whole block shares the number on its last line
+            let numbered = math.numbered && math.iter().all(|eq| eq.is_numbered());
+            let tagged = math.iter().any(|eq| eq.get_tag().is_some());
+            if !numbered && !tagged {
+                self.check_equation_labels(math.iter(), false);
+            }
+        } else {
+            self.check_equation_labels(math.iter(), math.numbered);
+        }
+        Ok(())
+    }
+
+    fn visit_list(&mut self, list: &List) -> Result<(), Error> {
+        if !list.options.is_empty() {
+            self.require("enumitem", "a list with options");
+        }
+        if let ListKind::Checklist(style) = list.kind {
+            self.require(style.package(), "a checklist");
+        }
+
+        for (i, item) in list.iter().enumerate() {
+            self.path.push(format!("[{}] item", i));
+            let result = self.visit_list_item(item);
+            self.path.pop();
+            result?;
+        }
+        Ok(())
+    }
+
+    fn visit_list_item(&mut self, item: &Item) -> Result<(), Error> {
+        if let Some(ref label) = item.label {
+            self.check_paragraph_element(label);
+        }
+        self.visit_paragraph(&item.content)?;
+        self.visit_children(item.iter())
+    }
+
+    fn visit_code_block(&mut self, _block: &CodeBlock) -> Result<(), Error> {
+        self.require_code_backend();
+        Ok(())
+    }
+
+    fn visit_environment(&mut self, env: &Environment) -> Result<(), Error> {
+        for arg in env.optional.iter().chain(&env.arguments) {
+            self.check_paragraph_element(arg);
+        }
+        self.visit_children(env.iter())
+    }
+
+    fn visit_theorem(&mut self, theorem: &Theorem) -> Result<(), Error> {
+        if !self.theorems.contains(&theorem.environment) {
+            self.report(
+                Severity::Error,
+                format!(
+                    "The {} environment was never declared with Preamble::new_theorem()",
+                    theorem.environment
+                ),
+            );
+        }
+        if let Some(ref title) = theorem.title {
+            self.check_paragraph_element(title);
+        }
+        self.visit_children(theorem.iter())
+    }
+
+    fn visit_proof(&mut self, proof: &Proof) -> Result<(), Error> {
+        self.require("amsthm", "the proof environment");
+        if let Some(ref title) = proof.title {
+            self.check_paragraph_element(title);
+        }
+        self.visit_children(proof.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use {
+        Chapter, Command, Equation, HeaderFooter, Length, PageGeometry, PageStyle, Section,
+        Subsection, Subsubsection, TheoremDefinition,
+    };
+
+    fn messages(doc: &Document) -> Vec<String> {
+        validate(doc)
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn a_valid_document_has_no_diagnostics() {
+        let mut section = Section::new("Intro");
+        section.push(Subsection::new("Background"));
+
+        let mut doc = Document::new(DocumentClass::Article);
+        doc.preamble.title("Report").author("Me");
+        doc.push(Element::TitlePage).push(section);
+
+        assert_eq!(messages(&doc), Vec::<String>::new());
+    }
+
+    #[test]
+    fn skipped_heading_levels() {
+        let mut chapter = Chapter::new("Methods");
+        chapter.push(Subsubsection::new("Detail"));
+
+        let mut doc = Document::new(DocumentClass::Book);
+        doc.push(chapter).push(Subsection::new("Stray"));
+
+        assert_eq!(
+            messages(&doc),
+            vec![
+                r"warning: \subsubsection skips 2 heading level(s) (at [0] \chapter{Methods} > [0] \subsubsection{Detail})",
+                r"warning: \subsection skips 2 heading level(s) (at [1] \subsection{Stray})",
+            ]
+        );
+    }
+
+    #[test]
+    fn headings_nested_inside_lower_levels() {
+        let mut subsection = Subsection::new("Outer");
+        subsection.push(Section::new("Inner"));
+        let mut section = Section::new("Top");
+        section.push(subsection);
+
+        let mut doc = Document::new(DocumentClass::Article);
+        doc.push(section);
+
+        let diagnostics = validate(&doc).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].path.len(), 3);
+    }
+
+    #[test]
+    fn labels_on_unnumbered_sections() {
+        let mut section = Section::new("Acknowledgements");
+        section.label("sec:thanks").numbered = false;
+
+        let mut doc = Document::new(DocumentClass::Article);
+        doc.push(section);
+
+        assert_eq!(
+            messages(&doc),
+            vec![
+                r#"warning: The label "sec:thanks" is on a \section* without a number (at [0] \section{Acknowledgements})"#
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_packages_are_reported_once() {
+        let mut math = DisplayMath::new(MathEnvironment::Gather);
+        math.push(Equation::new("a = b"));
+        let mut section = Section::new("Maths");
+        section
+            .push(Align::from("x &= y"))
+            .push(math)
+            .push(Paragraph::from(ParagraphElement::Link((
+                String::from("https://example.com"),
+                String::from("here"),
+            ))));
+
+        let mut doc = Document::new(DocumentClass::Article);
+        doc.push(section);
+
+        assert_eq!(
+            messages(&doc),
+            vec![
+                r"error: the align environment needs the amsmath package, but it isn't imported (at [0] \section{Maths} > [0] align)",
+                r"error: \href needs the hyperref package, but it isn't imported (at [0] \section{Maths} > [2] paragraph)",
+            ]
+        );
+
+        doc.preamble.use_package("amsmath").use_package("hyperref");
+        assert!(validate(&doc).unwrap().is_empty());
+    }
+
+    #[test]
+    fn a_validator_can_be_reused() {
+        let link =
+            ParagraphElement::Link((String::from("https://example.com"), String::from("here")));
+        let mut first = Document::new(DocumentClass::Article);
+        first.push(Paragraph::from(link));
+        let mut second = Document::new(DocumentClass::Article);
+        second.push("Plain text");
+
+        let mut validator = Validator::new();
+        validator.visit_document(&first).unwrap();
+        assert_eq!(validator.diagnostics().len(), 1);
+
+        validator.visit_document(&second).unwrap();
+        assert!(validator.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn labels_on_unnumbered_equations() {
+        let mut tagged = Equation::with_label("eq:tagged", "c = d");
+        tagged.tag("*");
+        let mut math = DisplayMath::unnumbered(MathEnvironment::Gather);
+        math.push(Equation::with_label("eq:lost", "a = b"))
+            .push(tagged);
+
+        let mut doc = Document::new(DocumentClass::Article);
+        doc.preamble.use_package("amsmath");
+        doc.push(math);
+
+        assert_eq!(
+            messages(&doc),
+            vec![
+                r#"warning: The label "eq:lost" is on an equation without a number (at [0] gather)"#
+            ]
+        );
+    }
+
+    #[test]
+    fn math_operators_in_text() {
+        let mut doc = Document::new(DocumentClass::Article);
+        let trace = doc.preamble.declare_math_operator("Tr", "Tr");
+        doc.push(Paragraph::from(ParagraphElement::from(
+            trace.invoke().unwrap(),
+        )))
+        .push(Paragraph::from(ParagraphElement::InlineMath(String::from(
+            r"\Tr(A)",
+        ))));
+
+        assert_eq!(
+            messages(&doc),
+            vec![
+                r"error: \Tr is a math operator, so it can only be used in maths (at [0] paragraph)"
+            ]
+        );
+    }
+
+    #[test]
+    fn unprintable_headings() {
+        let mut doc = Document::new(DocumentClass::Article);
+        doc.push(Section::new_formatted(Command::new("bad name").into()));
+
+        assert_eq!(
+            messages(&doc),
+            vec![r#"error: "bad name" isn't a valid command name (at [0] \section{...})"#]
+        );
+    }
+
+    #[test]
+    fn packages_imported_in_lists_and_raw_tex() {
+        let mut doc = Document::new(DocumentClass::Article);
+        doc.push(Align::from("x &= y"))
+            .push(Paragraph::from(ParagraphElement::Link((
+                String::from("https://example.com"),
+                String::from("here"),
+            ))));
+        doc.preamble
+            .use_package("amssymb, amsmath")
+            .push(PreambleElement::UserDefined(String::from(
+                r"\usepackage[colorlinks]{xcolor,hyperref}",
+            )));
+
+        assert!(validate(&doc).unwrap().is_empty());
+        assert!(doc.preamble.has_package("xcolor"));
+        assert!(!doc.preamble.has_package("colorlinks"));
+    }
+
+    #[test]
+    fn theorems_must_be_declared() {
+        let mut doc = Document::new(DocumentClass::Article);
+        doc.push(Theorem::new("lemma"));
+        assert_eq!(validate(&doc).unwrap().len(), 1);
+
+        doc.preamble
+            .new_theorem(TheoremDefinition::new("lemma", "Lemma"));
+        assert!(validate(&doc).unwrap().is_empty());
+    }
+
+    #[test]
+    fn changing_the_geometry() {
+        let mut landscape = PageGeometry::new();
+        landscape.landscape().margin(Length::cm(1.0));
+
+        let mut doc = Document::new(DocumentClass::Article);
+        doc.push(Element::NewGeometry(landscape))
+            .push(Element::RestoreGeometry);
+
+        assert_eq!(
+            messages(&doc),
+            vec![
+                r"warning: \newgeometry can't change the paper size or orientation, so they are ignored (at [0] \newgeometry)",
+                r"error: \newgeometry needs the geometry package, but it isn't imported (at [0] \newgeometry)",
+            ]
+        );
+
+        doc.page_geometry(PageGeometry::new());
+        assert_eq!(validate(&doc).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn class_specific_options() {
+        let mut doc = Document::new(DocumentClass::Report);
+        doc.class_option(ClassOption::TitlePage)
+            .class_option(ClassOption::OpenRight)
+            .push("No chapters yet");
+
+        assert_eq!(
+            messages(&doc),
+            vec![
+                "warning: The \"titlepage\" option is already the default for the report class",
+                "warning: The \"openright\" option only affects chapters, but there aren't any",
+            ]
+        );
+
+        doc.push(Chapter::new("A chapter"));
+        assert_eq!(validate(&doc).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn page_styles_must_be_defined() {
+        let mut style = PageStyle::new("report");
+        style.even_header(HeaderFooter::new());
+
+        let mut doc = Document::new(DocumentClass::Report);
+        doc.push(Element::PageStyle(String::from("report")))
+            .push(Element::ThisPageStyle(String::from("empty")))
+            .push(Element::PageStyle(String::from("fancy")));
+        assert_eq!(validate(&doc).unwrap().len(), 2);
+
+        doc.preamble.page_style(style);
+        assert_eq!(
+            messages(&doc),
+            vec![
+                "warning: The \"report\" page style has separate even pages, but the document is one-sided"
+            ]
+        );
+
+        doc.class_option(ClassOption::TwoSide);
+        assert!(validate(&doc).unwrap().is_empty());
+    }
+
+    #[test]
+    fn colours_in_page_styles_are_checked() {
+        let mut first = HeaderFooter::new();
+        first.center(ParagraphElement::colored(Color::named("brand"), "ACME"));
+        let mut style = PageStyle::new("report");
+        style
+            .footer
+            .right(ParagraphElement::colored(Color::Html(0x1_000000), "Draft"));
+        style.first_footer(first);
+
+        let mut doc = Document::new(DocumentClass::Report);
+        doc.preamble.use_package("xcolor").page_style(style);
+        doc.preamble.define_color("brand", Color::Rgb(0, 0, 0));
+
+        assert_eq!(
+            messages(&doc),
+            vec![
+                "error: 0x1000000 is too big to be an HTML colour",
+                "error: The \"brand\" colour was never defined",
+            ]
+        );
+    }
+
+    #[test]
+    fn plain_is_only_defined_once() {
+        let mut first = PageStyle::new("chapters");
+        first.first_footer(HeaderFooter::new());
+
+        let mut doc = Document::new(DocumentClass::Report);
+        doc.preamble
+            .page_style(PageStyle::new("plain"))
+            .page_style(first);
+
+        assert_eq!(
+            messages(&doc),
+            vec!["warning: The \"plain\" page style is defined more than once, only the last one is used"]
+        );
+    }
+
+    #[test]
+    fn colours_must_be_defined() {
+        let mut doc = Document::new(DocumentClass::Article);
+        doc.push(Paragraph::from(ParagraphElement::framed_color_box(
+            "brand",
+            Color::named("brand").percent(10),
+            ParagraphElement::colored(Color::Rgb(1, 2, 3).percent(50), "Hi"),
+        )));
+
+        assert_eq!(
+            messages(&doc),
+            vec![
+                "error: The \"brand\" colour was never defined (at [0] paragraph)",
+                "error: The \"brand\" colour was never defined (at [0] paragraph)",
+                "error: Rgb(1, 2, 3) can't be mixed, give it a name with Preamble::define_color() first (at [0] paragraph)",
+                r"error: \fcolorbox needs the xcolor package, but it isn't imported (at [0] paragraph)",
+            ]
+        );
+
+        doc.preamble.define_color("brand", Color::Html(0xABCDEF));
+        assert_eq!(validate(&doc).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn partial_documents_with_a_preamble() {
+        let mut doc = Document::new(DocumentClass::Part);
+        doc.preamble.use_package("amsmath");
+        doc.push(Element::TitlePage).push(Align::from("x &= y"));
+
+        assert_eq!(
+            messages(&doc),
+            vec!["warning: The preamble of a partial document is never written out"]
+        );
+    }
+}